@@ -1,21 +1,662 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
 
-#[ink::contract]
+#[openbrush::contract]
 mod managing_logic {
-    use ink_prelude::vec::Vec;
-    use openbrush::traits::{ String };
+    use ink::prelude::vec::Vec;
+    use ink::ToAccountId;
+    use openbrush::traits::{ Storage, String };
+    use openbrush::contracts::traits::psp22::PSP22Ref;
+    use openbrush::contracts::traits::psp37::PSP37Ref;
 
-    use document_management_platform::traits::managing_logic::*;
-    use document_management_platform::impls::managing_logic::data_structure::*;
+    use document_management_platform::impls::managing_logic::managing_logic::*;
 
     use native_token::native_token::NativeTokenRef;
-    use nft_token::nft_token::{ NftTokenRef, PSP37MintableRef };
+    use nft_token::nft_token::NftTokenRef;
 
     #[ink(storage)]
+    #[derive(Default, Storage)]
     pub struct NftCollection {
-        docs: Vec<NftDocument>,
-        native_token_ref: NativeTokenRef,
-        nft_token_ref: NftTokenRef,
+        #[storage_field]
+        managing_logic: managing_logic::Data,
+    }
+
+    /// Event emitted when a document is registered on the platform.
+    #[ink(event)]
+    pub struct DocumentCreated {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        title: String,
+    }
+
+    /// Event emitted when a contributor uploads a new version of a document. The locator is
+    /// left out for private documents.
+    #[ink(event)]
+    pub struct VersionCreated {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        contributor: AccountId,
+        version_id: u32,
+        locator: Option<ContentLocator>,
+    }
+
+    /// Event emitted when a version becomes the published version of a document.
+    #[ink(event)]
+    pub struct VersionPublished {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        contributor: AccountId,
+        version_id: u32,
+    }
+
+    /// Event emitted when the published version of a document is archived.
+    #[ink(event)]
+    pub struct VersionArchived {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        contributor: AccountId,
+        version_id: u32,
+    }
+
+    /// Event emitted when a contributor submits a draft version for review.
+    #[ink(event)]
+    pub struct VersionSubmitted {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        contributor: AccountId,
+        version_id: u32,
+    }
+
+    /// Event emitted when a reviewer rejects a version pending review.
+    #[ink(event)]
+    pub struct VersionRejected {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        contributor: AccountId,
+        #[ink(topic)]
+        reviewer: AccountId,
+        version_id: u32,
+        reason: String,
+    }
+
+    /// Event emitted when a document changes owner.
+    #[ink(event)]
+    pub struct DocumentOwnershipTransferred {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Event emitted when an owner adds a collaborator to a document.
+    #[ink(event)]
+    pub struct CollaboratorAdded {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        collaborator: AccountId,
+        user_role: UserRole,
+    }
+
+    /// Event emitted when an owner changes the role of a collaborator.
+    #[ink(event)]
+    pub struct CollaboratorRoleChanged {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        collaborator: AccountId,
+        user_role: UserRole,
+    }
+
+    /// Event emitted when an owner removes a collaborator from a document.
+    #[ink(event)]
+    pub struct CollaboratorRemoved {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        collaborator: AccountId,
+    }
+
+    /// Event emitted when an owner adds a reviewer to a document.
+    #[ink(event)]
+    pub struct ReviewerAdded {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        reviewer: AccountId,
+    }
+
+    /// Event emitted when an owner removes a reviewer from a document.
+    #[ink(event)]
+    pub struct ReviewerRemoved {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        reviewer: AccountId,
+    }
+
+    /// Event emitted when a contributor converts accrued points into native tokens.
+    #[ink(event)]
+    pub struct RewardsClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        points: u32,
+        amount: Balance,
+    }
+
+    /// Event emitted when the admin lets an account earn points for the versions it approves.
+    #[ink(event)]
+    pub struct PlatformReviewerAdded {
+        #[ink(topic)]
+        reviewer: AccountId,
+    }
+
+    /// Event emitted when the admin withdraws an account from the platform reviewers.
+    #[ink(event)]
+    pub struct PlatformReviewerRemoved {
+        #[ink(topic)]
+        reviewer: AccountId,
+    }
+
+    /// Event emitted when someone pays native tokens to the shareholders of a document.
+    #[ink(event)]
+    pub struct DocumentPaid {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        payer: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a shareholder claims its part of the payments to a document.
+    #[ink(event)]
+    pub struct RevenueClaimed {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when an owner changes who can find and read a document.
+    #[ink(event)]
+    pub struct VisibilityChanged {
+        #[ink(topic)]
+        doc_id: u32,
+        visibility: Visibility,
+    }
+
+    /// Event emitted when an owner hands the encrypted content key of a version to an account.
+    #[ink(event)]
+    pub struct KeyEnvelopeGranted {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        recipient: AccountId,
+        version_id: u32,
+    }
+
+    /// Event emitted when an owner withdraws the content key of a version from an account.
+    #[ink(event)]
+    pub struct KeyEnvelopeRevoked {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        recipient: AccountId,
+        version_id: u32,
+    }
+
+    /// Event emitted when an owner retracts a document.
+    #[ink(event)]
+    pub struct DocumentRetracted {
+        #[ink(topic)]
+        doc_id: u32,
+    }
+
+    /// Event emitted when an owner deletes a document and burns its shares.
+    #[ink(event)]
+    pub struct DocumentDeleted {
+        #[ink(topic)]
+        doc_id: u32,
+    }
+
+    /// Event emitted when a document is created as a fork of a version of another one.
+    #[ink(event)]
+    pub struct DocumentForked {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        parent_doc_id: u32,
+        version_id: u32,
+    }
+
+    /// Event emitted when a fork pays part of its revenue to the owner of its parent.
+    #[ink(event)]
+    pub struct RoyaltyPaid {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        parent_doc_id: u32,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a version of a document declares a reference to another document.
+    #[ink(event)]
+    pub struct CitationAdded {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        cited_doc_id: u32,
+        version_id: u32,
+        cited_version_id: Option<u32>,
+    }
+
+    /// Event emitted when someone comments on a version or replies to a comment.
+    #[ink(event)]
+    pub struct CommentPosted {
+        #[ink(topic)]
+        comment_id: u32,
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        author: AccountId,
+        version_id: u32,
+        parent_id: Option<u32>,
+    }
+
+    /// Event emitted when an author edits a comment.
+    #[ink(event)]
+    pub struct CommentEdited {
+        #[ink(topic)]
+        comment_id: u32,
+        #[ink(topic)]
+        doc_id: u32,
+    }
+
+    /// Event emitted when an author or the document owner deletes a comment.
+    #[ink(event)]
+    pub struct CommentDeleted {
+        #[ink(topic)]
+        comment_id: u32,
+        #[ink(topic)]
+        doc_id: u32,
+        by: AccountId,
+    }
+
+    /// Event emitted when an owner replaces the metadata of a document.
+    #[ink(event)]
+    pub struct MetadataUpdated {
+        #[ink(topic)]
+        doc_id: u32,
+    }
+
+    /// Event emitted when an owner lists another location of the content of a version. The
+    /// locator is left out for private documents.
+    #[ink(event)]
+    pub struct MirrorAdded {
+        #[ink(topic)]
+        doc_id: u32,
+        version_id: u32,
+        locator: Option<ContentLocator>,
+    }
+
+    /// Event emitted when a reader buys access to a document.
+    #[ink(event)]
+    pub struct AccessPurchased {
+        #[ink(topic)]
+        doc_id: u32,
+        #[ink(topic)]
+        buyer: AccountId,
+        price: Balance,
+        expires_at: Option<Timestamp>,
+    }
+
+    /// Event emitted when the admin withdraws the platform fees collected from license sales.
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    impl ManagingLogic for NftCollection {}
+
+    impl managing_logic::Internal for NftCollection {
+        fn _emit_document_created_event(&self, doc_id: u32, owner: AccountId, title: String) {
+            self.env().emit_event(DocumentCreated {
+                doc_id,
+                owner,
+                title,
+            });
+        }
+
+        fn _emit_version_created_event(
+            &self,
+            doc_id: u32,
+            contributor: AccountId,
+            version_id: u32,
+            locator: Option<ContentLocator>
+        ) {
+            self.env().emit_event(VersionCreated {
+                doc_id,
+                contributor,
+                version_id,
+                locator,
+            });
+        }
+
+        fn _emit_version_published_event(
+            &self,
+            doc_id: u32,
+            contributor: AccountId,
+            version_id: u32
+        ) {
+            self.env().emit_event(VersionPublished {
+                doc_id,
+                contributor,
+                version_id,
+            });
+        }
+
+        fn _emit_version_archived_event(
+            &self,
+            doc_id: u32,
+            contributor: AccountId,
+            version_id: u32
+        ) {
+            self.env().emit_event(VersionArchived {
+                doc_id,
+                contributor,
+                version_id,
+            });
+        }
+
+        fn _emit_version_submitted_event(
+            &self,
+            doc_id: u32,
+            contributor: AccountId,
+            version_id: u32
+        ) {
+            self.env().emit_event(VersionSubmitted {
+                doc_id,
+                contributor,
+                version_id,
+            });
+        }
+
+        fn _emit_version_rejected_event(
+            &self,
+            doc_id: u32,
+            contributor: AccountId,
+            reviewer: AccountId,
+            version_id: u32,
+            reason: String
+        ) {
+            self.env().emit_event(VersionRejected {
+                doc_id,
+                contributor,
+                reviewer,
+                version_id,
+                reason,
+            });
+        }
+
+        fn _emit_document_ownership_transferred_event(
+            &self,
+            doc_id: u32,
+            from: AccountId,
+            to: AccountId
+        ) {
+            self.env().emit_event(DocumentOwnershipTransferred {
+                doc_id,
+                from,
+                to,
+            });
+        }
+
+        fn _emit_collaborator_added_event(
+            &self,
+            doc_id: u32,
+            collaborator: AccountId,
+            user_role: UserRole
+        ) {
+            self.env().emit_event(CollaboratorAdded {
+                doc_id,
+                collaborator,
+                user_role,
+            });
+        }
+
+        fn _emit_collaborator_role_changed_event(
+            &self,
+            doc_id: u32,
+            collaborator: AccountId,
+            user_role: UserRole
+        ) {
+            self.env().emit_event(CollaboratorRoleChanged {
+                doc_id,
+                collaborator,
+                user_role,
+            });
+        }
+
+        fn _emit_collaborator_removed_event(&self, doc_id: u32, collaborator: AccountId) {
+            self.env().emit_event(CollaboratorRemoved {
+                doc_id,
+                collaborator,
+            });
+        }
+
+        fn _emit_reviewer_added_event(&self, doc_id: u32, reviewer: AccountId) {
+            self.env().emit_event(ReviewerAdded {
+                doc_id,
+                reviewer,
+            });
+        }
+
+        fn _emit_reviewer_removed_event(&self, doc_id: u32, reviewer: AccountId) {
+            self.env().emit_event(ReviewerRemoved {
+                doc_id,
+                reviewer,
+            });
+        }
+
+        fn _emit_rewards_claimed_event(&self, account: AccountId, points: u32, amount: Balance) {
+            self.env().emit_event(RewardsClaimed {
+                account,
+                points,
+                amount,
+            });
+        }
+
+        fn _emit_platform_reviewer_added_event(&self, reviewer: AccountId) {
+            self.env().emit_event(PlatformReviewerAdded {
+                reviewer,
+            });
+        }
+
+        fn _emit_platform_reviewer_removed_event(&self, reviewer: AccountId) {
+            self.env().emit_event(PlatformReviewerRemoved {
+                reviewer,
+            });
+        }
+
+        fn _emit_document_paid_event(&self, doc_id: u32, payer: AccountId, amount: Balance) {
+            self.env().emit_event(DocumentPaid {
+                doc_id,
+                payer,
+                amount,
+            });
+        }
+
+        fn _emit_visibility_changed_event(&self, doc_id: u32, visibility: Visibility) {
+            self.env().emit_event(VisibilityChanged {
+                doc_id,
+                visibility,
+            });
+        }
+
+        fn _emit_key_envelope_granted_event(
+            &self,
+            doc_id: u32,
+            version_id: u32,
+            recipient: AccountId
+        ) {
+            self.env().emit_event(KeyEnvelopeGranted {
+                doc_id,
+                recipient,
+                version_id,
+            });
+        }
+
+        fn _emit_key_envelope_revoked_event(
+            &self,
+            doc_id: u32,
+            version_id: u32,
+            recipient: AccountId
+        ) {
+            self.env().emit_event(KeyEnvelopeRevoked {
+                doc_id,
+                recipient,
+                version_id,
+            });
+        }
+
+        fn _emit_document_retracted_event(&self, doc_id: u32) {
+            self.env().emit_event(DocumentRetracted {
+                doc_id,
+            });
+        }
+
+        fn _emit_document_deleted_event(&self, doc_id: u32) {
+            self.env().emit_event(DocumentDeleted {
+                doc_id,
+            });
+        }
+
+        fn _emit_metadata_updated_event(&self, doc_id: u32) {
+            self.env().emit_event(MetadataUpdated {
+                doc_id,
+            });
+        }
+
+        fn _emit_document_forked_event(&self, doc_id: u32, parent_doc_id: u32, version_id: u32) {
+            self.env().emit_event(DocumentForked {
+                doc_id,
+                parent_doc_id,
+                version_id,
+            });
+        }
+
+        fn _emit_royalty_paid_event(
+            &self,
+            doc_id: u32,
+            parent_doc_id: u32,
+            recipient: AccountId,
+            amount: Balance
+        ) {
+            self.env().emit_event(RoyaltyPaid {
+                doc_id,
+                parent_doc_id,
+                recipient,
+                amount,
+            });
+        }
+
+        fn _emit_citation_added_event(&self, doc_id: u32, version_id: u32, cited: Citation) {
+            self.env().emit_event(CitationAdded {
+                doc_id,
+                cited_doc_id: cited.doc_id,
+                version_id,
+                cited_version_id: cited.version_id,
+            });
+        }
+
+        fn _emit_comment_posted_event(
+            &self,
+            comment_id: u32,
+            doc_id: u32,
+            version_id: u32,
+            author: AccountId,
+            parent_id: Option<u32>
+        ) {
+            self.env().emit_event(CommentPosted {
+                comment_id,
+                doc_id,
+                author,
+                version_id,
+                parent_id,
+            });
+        }
+
+        fn _emit_comment_edited_event(&self, comment_id: u32, doc_id: u32) {
+            self.env().emit_event(CommentEdited {
+                comment_id,
+                doc_id,
+            });
+        }
+
+        fn _emit_comment_deleted_event(&self, comment_id: u32, doc_id: u32, by: AccountId) {
+            self.env().emit_event(CommentDeleted {
+                comment_id,
+                doc_id,
+                by,
+            });
+        }
+
+        fn _emit_mirror_added_event(
+            &self,
+            doc_id: u32,
+            version_id: u32,
+            locator: Option<ContentLocator>
+        ) {
+            self.env().emit_event(MirrorAdded {
+                doc_id,
+                version_id,
+                locator,
+            });
+        }
+
+        fn _emit_access_purchased_event(
+            &self,
+            doc_id: u32,
+            buyer: AccountId,
+            price: Balance,
+            expires_at: Option<Timestamp>
+        ) {
+            self.env().emit_event(AccessPurchased {
+                doc_id,
+                buyer,
+                price,
+                expires_at,
+            });
+        }
+
+        fn _emit_fees_withdrawn_event(&self, to: AccountId, amount: Balance) {
+            self.env().emit_event(FeesWithdrawn {
+                to,
+                amount,
+            });
+        }
+
+        fn _emit_revenue_claimed_event(&self, doc_id: u32, account: AccountId, amount: Balance) {
+            self.env().emit_event(RevenueClaimed {
+                doc_id,
+                account,
+                amount,
+            });
+        }
     }
 
     impl NftCollection {
@@ -26,144 +667,92 @@ mod managing_logic {
             initial_supply: Balance,
             name: Option<String>,
             symbol: Option<String>,
-            decimal: u8
+            decimal: u8,
+            reward_per_point: Balance,
+            salt: Vec<u8>
         ) -> Self {
             let native_token_contract = NativeTokenRef::new(initial_supply, name, symbol, decimal)
                 .code_hash(native_token_code_hash)
                 .endowment(0)
-                .salt_bytes([0xde, 0xad, 0xbe, 0xef])
-                .instantiate();
-
-            let nft_token_contract = NftTokenRef::new()
-                .code_hash(nft_token_code_hash)
-                .endowment(0)
-                .salt_bytes([0xde, 0xad, 0xbe, 0xef])
+                .salt_bytes(&salt)
                 .instantiate();
+            let nft_token = Self::instantiate_nft_token(nft_token_code_hash, &salt);
 
-            Self {
-                docs: Vec::new(),
-                native_token_ref: native_token_contract,
-                nft_token_ref: nft_token_contract,
-            }
+            Self::init(native_token_contract.to_account_id(), nft_token, reward_per_point)
         }
 
-        #[ink(message)]
-        pub fn get_nfts(&self) -> Vec<NftDocument> {
-            self.docs.clone()
-        }
+        /// Uses an already deployed PSP22 as the platform currency and instantiates a fresh
+        /// nft_token contract owned by the platform.
+        #[ink(constructor)]
+        pub fn new_with_native_token(
+            native_token: AccountId,
+            nft_token_code_hash: Hash,
+            reward_per_point: Balance,
+            salt: Vec<u8>
+        ) -> Result<Self, Error> {
+            Self::ensure_psp22(native_token)?;
+            let nft_token = Self::instantiate_nft_token(nft_token_code_hash, &salt);
 
-        #[ink(message)]
-        pub fn get_nfts_length(&self) -> u32 {
-            self.docs.len() as u32
+            Ok(Self::init(native_token, nft_token, reward_per_point))
         }
 
-        #[ink(message)]
-        pub fn get_version_doc_length(&self, docId: u32) -> u8 {
-            self.docs[doc_id as usize].versions.len() as u8
-        }
+        /// Attaches the platform to already deployed token contracts.
+        ///
+        /// Ownership of the nft_token contract has to be transferred to the platform before
+        /// documents can be created, since minting and attributes are owner-only.
+        #[ink(constructor)]
+        pub fn new_with_tokens(
+            native_token: AccountId,
+            nft_token: AccountId,
+            reward_per_point: Balance
+        ) -> Result<Self, Error> {
+            Self::ensure_psp22(native_token)?;
+            Self::ensure_psp37(nft_token)?;
 
-        #[ink(message)]
-        pub fn add_nft(&mut self, nft: NftDocument) {
-            self.docs.push(nft);
+            Ok(Self::init(native_token, nft_token, reward_per_point))
         }
 
-        #[ink(message)]
-        pub fn get_document(&self, docId: u32) -> Option<NftDocument> {
-            self.docs.get(docId as usize).cloned()
+        fn init(native_token: AccountId, nft_token: AccountId, reward_per_point: Balance) -> Self {
+            let mut _instance = Self::default();
+            _instance.managing_logic.reward_per_point = reward_per_point;
+            _instance.managing_logic.admin = _instance.env().caller();
+            _instance.managing_logic.native_token = native_token;
+            _instance.managing_logic.nft_token = nft_token;
+            _instance
         }
 
+        /// Replaces the code of the platform contract, keeping its storage.
         #[ink(message)]
-        pub fn create_document(&mut self, title: String, ipfs_hash: String) -> NftDocument {
-            let post_id = self.get_nfts_length();
-            let caller = self.env().caller();
-
-            let new_version = Version {
-                version_id: 0,
-                contributor: caller,
-                ipfs_hash: ipfs_hash.clone(),
-                state: DocumentState::Publish,
-            };
-
-            let tokenId: Id = nft_token::nft_token::Id::U32(post_id);
-            let tokenBalance: u128 = 100000;
-            let initVersionId: u8 = 0;
-            let versionId = vec![initVersionId];
-            let ids_amount: Vec<(Id, Balance)> = vec![(tokenId.clone(), tokenBalance)];
-
-            self.nft_token._mint(caller, ids_amount);
-            self.nft_token.set_attribute(tokenId, versionId, ipfs_hash.clone());
-
-            let mut versions = Vec::new();
-            versions.push(new_version);
-
-            let vec_post_owner = Vec::new();
-
-            let new_doc = NftDocument {
-                id: post_id,
-                owner: caller,
-                title: title.clone(),
-                ipfs_hash_doc: ipfs_hash.clone(),
-                post_owner: vec_post_owner.clone(),
-                versions: versions.clone(),
-                version_id_publish: 0,
-                number_upload: 1,
-            };
-
-            self.add_nft(new_doc);
-
-            NftDocument {
-                id: post_id,
-                owner: caller,
-                title,
-                ipfs_hash_doc: ipfs_hash,
-                post_owner: vec_post_owner,
-                versions,
-                version_id_publish: 0,
-                number_upload: 1,
-            }
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), Error> {
+            self._ensure_admin()?;
+
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)
         }
 
-        #[ink(message)]
-        pub fn create_version_document(&mut self, doc_id: u32, ipfs_hash: String) -> Version {
-            let caller = self.env().caller();
-            let next_version_id = self.get_version_doc_length(doc_id);
-
-            let token_id: Id = nft_token::nft_token::Id::U32(doc_id);
-            let version_id = vec![next_version_id];
-
-            let new_version = Version {
-                version_id: next_version_id,
-                contributor: caller,
-                ipfs_hash: ipfs_hash.clone(),
-                state: DocumentState::Publish,
-            };
-
-            self.nft_token.set_attribute(token_id, version_id, ipfs_hash.clone());
-
-            let publish = self.docs[doc_id as usize].version_id_publish;
-
-            self.docs[doc_id as usize].ipfs_hash_doc = ipfs_hash.clone();
-            self.docs[doc_id as usize].versions[publish as usize].state = DocumentState::Archived;
-            self.docs[doc_id as usize].versions.push(new_version);
-            self.docs[doc_id as usize].version_id_publish = next_version_id;
-            self.docs[doc_id as usize].number_upload += 1;
-
-            Version {
-                version_id: next_version_id,
-                contributor: caller,
-                ipfs_hash: ipfs_hash,
-                state: DocumentState::Publish,
-            }
+        // Instantiated from the constructor, so this contract becomes the token owner
+        // and is the only account allowed to mint and set attributes.
+        fn instantiate_nft_token(code_hash: Hash, salt: &[u8]) -> AccountId {
+            let nft_token_contract = NftTokenRef::new()
+                .code_hash(code_hash)
+                .endowment(0)
+                .salt_bytes(salt)
+                .instantiate();
+
+            nft_token_contract.to_account_id()
         }
 
-        #[ink(message)]
-        pub fn get_total_supply(&self, token_id: Option<Id>) -> Balance {
-            return self.nft_token_ref._total_supply(token_id);
+        fn ensure_psp22(native_token: AccountId) -> Result<(), Error> {
+            match PSP22Ref::total_supply_builder(&native_token).try_invoke() {
+                Ok(Ok(_)) => Ok(()),
+                _ => Err(Error::InvalidTokenContract),
+            }
         }
 
-        #[ink(message)]
-        pub fn get_attribute_version(&self, token_id: Id, version_id: Vec<u8>) -> Option<Vec<u8>> {
-            return self.nft_token_ref.get_attribute(token_id, version_id);
+        fn ensure_psp37(nft_token: AccountId) -> Result<(), Error> {
+            match PSP37Ref::total_supply_builder(&nft_token, None).try_invoke() {
+                Ok(Ok(_)) => Ok(()),
+                _ => Err(Error::InvalidTokenContract),
+            }
         }
     }
-}
\ No newline at end of file
+}
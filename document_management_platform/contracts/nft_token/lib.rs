@@ -12,7 +12,10 @@ pub mod nft_token {
         modifiers,
         traits::{ Storage, String },
     };
-    use document_management_platform::traits::nft_token::*;
+    use document_management_platform::traits::{
+        managing_logic::ManagingLogicRef,
+        nft_token::*,
+    };
 
     #[ink(storage)]
     #[derive(Default, Storage)]
@@ -43,6 +46,59 @@ pub mod nft_token {
         }
     }
 
+    impl NftTokenAttributes for NftToken {
+        #[ink(message)]
+        #[openbrush::modifiers(only_owner)]
+        fn set_attribute(
+            &mut self,
+            id: Id,
+            key: Vec<u8>,
+            data: Vec<u8>
+        ) -> Result<(), PSP37Error> {
+            self._set_attribute(&id, &key, &data)
+        }
+    }
+
+    impl psp37::Transfer for NftToken {
+        // Shares moved by holders themselves are reported to the platform, which owns this
+        // contract, so it can settle the revenue earned so far and keep the document owner in
+        // line with the shares. The platform handles its own mints, burns and transfers.
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            ids: &Vec<(Id, Balance)>
+        ) -> Result<(), PSP37Error> {
+            let platform = self.owner();
+
+            let (from, to) = match (from, to) {
+                (Some(from), Some(to)) if self.env().caller() != platform => (*from, *to),
+                _ => {
+                    return Ok(());
+                }
+            };
+
+            for (id, amount) in ids {
+                let from_balance = self.balance_of(from, Some(id.clone()));
+                let to_balance = self.balance_of(to, Some(id.clone()));
+                let total_supply = self.total_supply(Some(id.clone()));
+
+                ManagingLogicRef::on_shares_transfer(
+                    &platform,
+                    from,
+                    to,
+                    id.clone(),
+                    *amount,
+                    from_balance,
+                    to_balance,
+                    total_supply
+                )?;
+            }
+
+            Ok(())
+        }
+    }
+
     impl PSP37Mintable for NftToken {
         #[ink(message)]
         #[openbrush::modifiers(only_owner)]
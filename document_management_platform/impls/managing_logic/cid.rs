@@ -0,0 +1,186 @@
+use ink::prelude::vec::Vec;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Multihash code of sha2-256, the only hash function a CIDv0 can use.
+const SHA2_256: u64 = 0x12;
+
+/// Whether `cid` is a well formed IPFS content identifier.
+///
+/// CIDv0 is the base58btc encoding of a sha2-256 multihash (`Qm…`). CIDv1 is a multibase
+/// string (base32, base58btc or base16) holding the version, the content multicodec and a
+/// multihash whose digest length matches the declared one.
+pub fn is_valid_cid(cid: &str) -> bool {
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        return match decode_base58(cid) {
+            Some(bytes) => bytes.len() == 34 && bytes[0] == (SHA2_256 as u8) && bytes[1] == 32,
+            None => false,
+        };
+    }
+
+    let mut chars = cid.chars();
+    let bytes = match chars.next() {
+        Some('b') => decode_base32(chars.as_str()),
+        Some('B') => decode_base32(&chars.as_str().to_ascii_lowercase()),
+        Some('z') => decode_base58(chars.as_str()),
+        Some('f') | Some('F') => decode_base16(chars.as_str()),
+        _ => None,
+    };
+
+    match bytes {
+        Some(bytes) => is_valid_cid_v1(&bytes),
+        None => false,
+    }
+}
+
+fn is_valid_cid_v1(bytes: &[u8]) -> bool {
+    let mut rest = bytes;
+
+    let version = read_varint(&mut rest);
+    let codec = read_varint(&mut rest);
+    let hash_code = read_varint(&mut rest);
+    let digest_length = read_varint(&mut rest);
+
+    match (version, codec, hash_code, digest_length) {
+        (Some(1), Some(_), Some(_), Some(length)) => length > 0 && (rest.len() as u64) == length,
+        _ => false,
+    }
+}
+
+/// Reads an unsigned LEB128 varint, which multiformats limit to nine bytes.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value: u64 = 0;
+
+    for (index, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[index + 1..];
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+fn decode_base58(input: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Every leading '1' stands for a leading zero byte.
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    bytes.extend(core::iter::repeat(0).take(zeros));
+    bytes.reverse();
+
+    Some(bytes)
+}
+
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity((input.len() * 5) / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+
+        buffer = (buffer << 5) | value;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+fn decode_base16(input: &str) -> Option<Vec<u8>> {
+    if input.len() % 2 != 0 {
+        return None;
+    }
+
+    input
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CID_V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const CID_V1_BASE32: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    const CID_V1_BASE58: &str = "zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7";
+    const CID_V1_BASE16: &str =
+        "f01701220c3c4733ec8affd06cf9e9ff50ffc6bcd2ec85a6170004bb709669c31de94391a";
+
+    #[test]
+    fn accepts_cid_v0() {
+        assert!(is_valid_cid(CID_V0));
+    }
+
+    #[test]
+    fn accepts_cid_v1_in_every_supported_base() {
+        assert!(is_valid_cid(CID_V1_BASE32));
+        assert!(is_valid_cid(&CID_V1_BASE32.to_ascii_uppercase()));
+        assert!(is_valid_cid(CID_V1_BASE58));
+        assert!(is_valid_cid(CID_V1_BASE16));
+        assert!(is_valid_cid(&CID_V1_BASE16.to_ascii_uppercase()));
+    }
+
+    #[test]
+    fn rejects_cid_v0_of_the_wrong_length() {
+        assert!(!is_valid_cid(&CID_V0[..45]));
+        assert!(!is_valid_cid(&[CID_V0, "G"].concat()));
+        assert!(!is_valid_cid("Qm"));
+    }
+
+    #[test]
+    fn rejects_characters_outside_the_alphabet() {
+        // '0', 'O', 'I' and 'l' are not part of base58btc; '1' and '8' are not part of base32.
+        assert!(!is_valid_cid(&CID_V0.replacen('G', "0", 1)));
+        assert!(!is_valid_cid(&CID_V0.replacen('A', "O", 1)));
+        assert!(!is_valid_cid(&CID_V1_BASE32.replacen('y', "1", 1)));
+        assert!(!is_valid_cid(&CID_V1_BASE16.replacen('c', "g", 1)));
+    }
+
+    #[test]
+    fn rejects_truncated_digests() {
+        assert!(!is_valid_cid(&CID_V1_BASE32[..CID_V1_BASE32.len() - 1]));
+        assert!(!is_valid_cid(&CID_V1_BASE58[..CID_V1_BASE58.len() - 1]));
+        assert!(!is_valid_cid(&CID_V1_BASE16[..CID_V1_BASE16.len() - 2]));
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_prefixes() {
+        // Same bytes as the base16 CID with the version set to 2.
+        assert!(!is_valid_cid(&CID_V1_BASE16.replacen("f01", "f02", 1)));
+        assert!(!is_valid_cid(&CID_V1_BASE32.replacen('b', "m", 1)));
+        assert!(!is_valid_cid(""));
+        assert!(!is_valid_cid("hello"));
+    }
+}
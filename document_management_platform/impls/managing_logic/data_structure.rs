@@ -1,8 +1,10 @@
+use crate::impls::managing_logic::{ cid::is_valid_cid, spdx::is_valid_spdx_expression };
 use crate::traits::managing_logic::*;
-use openbrush::traits::{ AccountId, String };
+use openbrush::traits::{ AccountId, Balance, String, Timestamp };
+use ink::prelude::vec::Vec;
 
 #[derive(scale::Encode, scale::Decode, Clone, Debug)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub struct PostOwner {
     pub user: AccountId,
     pub user_role: UserRole,
@@ -10,23 +12,217 @@ pub struct PostOwner {
 }
 
 #[derive(scale::Encode, scale::Decode, Clone, Debug)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub struct Version {
+    pub version_id: u32,
+    pub contributor: AccountId,
+    /// Where the content was uploaded; left out, along with mirrors, for callers who may not
+    /// see it.
+    pub locator: Option<ContentLocator>,
+    pub state: DocumentState,
+    pub mirrors: Vec<ContentLocator>,
+}
+
+impl Version {
+    /// Every place the content of this version can be fetched from, starting with its primary
+    /// locator.
+    pub fn locators(&self) -> Vec<ContentLocator> {
+        self.locator.iter().chain(self.mirrors.iter()).cloned().collect()
+    }
+}
+
+/// Where the content of a version is stored.
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum ContentLocator {
+    Ipfs(String),
+    /// Arweave transaction id.
+    Arweave(String),
+    /// HTTPS location along with the sha2-256 digest of the content served there.
+    Url {
+        url: String,
+        digest: [u8; 32],
+    },
+}
+
+impl ContentLocator {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ContentLocator::Ipfs(cid) => is_valid_cid(cid),
+            ContentLocator::Arweave(tx_id) =>
+                tx_id.len() == 43 &&
+                    tx_id.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'),
+            ContentLocator::Url { url, .. } =>
+                url.starts_with("https://") &&
+                    url.len() > 8 &&
+                    url.len() <= (MAX_URL_LENGTH as usize),
+        }
+    }
+}
+
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct NftDocument {
+    pub id: u32,
+    pub owner: AccountId,
+    pub title: String,
+    /// Primary locator of the published version.
+    pub locator: Option<ContentLocator>,
+    pub post_owner: Vec<PostOwner>,
+    pub reviewers: Vec<AccountId>,
+    pub version_id_publish: u32,
+    pub number_upload: u32,
+    pub visibility: Visibility,
+}
+
+/// Layout of a `Version` stored before version ids were widened to `u32`.
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct LegacyVersion {
     pub version_id: u8,
     pub contributor: AccountId,
     pub ipfs_hash: String,
     pub state: DocumentState,
 }
 
+/// Layout of an `NftDocument` stored before version ids were widened to `u32`.
 #[derive(scale::Encode, scale::Decode, Clone, Debug)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-pub struct NftDocument {
+pub struct LegacyNftDocument {
     pub id: u32,
     pub owner: AccountId,
     pub title: String,
     pub ipfs_hash_doc: String,
     pub post_owner: Vec<PostOwner>,
-    pub versions: Vec<Version>,
+    pub reviewers: Vec<AccountId>,
     pub version_id_publish: u8,
     pub number_upload: u32,
-}
\ No newline at end of file
+}
+
+impl From<LegacyVersion> for Version {
+    fn from(version: LegacyVersion) -> Self {
+        Version {
+            version_id: version.version_id as u32,
+            contributor: version.contributor,
+            locator: Some(ContentLocator::Ipfs(version.ipfs_hash)),
+            state: version.state,
+            mirrors: Vec::new(),
+        }
+    }
+}
+
+impl From<LegacyNftDocument> for NftDocument {
+    fn from(doc: LegacyNftDocument) -> Self {
+        NftDocument {
+            id: doc.id,
+            owner: doc.owner,
+            title: doc.title,
+            locator: Some(ContentLocator::Ipfs(doc.ipfs_hash_doc)),
+            post_owner: doc.post_owner,
+            reviewers: doc.reviewers,
+            version_id_publish: doc.version_id_publish as u32,
+            number_upload: doc.number_upload,
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+/// Price an owner asks for reading a document; `duration` is `None` for perpetual licenses.
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct AccessTerms {
+    pub price: Balance,
+    pub duration: Option<Timestamp>,
+}
+
+/// Access bought by a reader; `expires_at` is `None` for perpetual licenses.
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct License {
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Optional catalogue information about a document.
+#[derive(scale::Encode, scale::Decode, Clone, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct DocumentMetadata {
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// SPDX license expression, e.g. `CC-BY-4.0`.
+    pub license: Option<String>,
+    pub language: Option<String>,
+    pub mime_type: Option<String>,
+    /// Size of the content in bytes.
+    pub size: Option<u64>,
+}
+
+impl DocumentMetadata {
+    /// Whether the text fields fit their length limits and the license is a valid SPDX
+    /// expression. Tags are checked separately.
+    pub fn is_valid(&self) -> bool {
+        let fits = |field: &Option<String>, max_length: u32| match field {
+            Some(field) => field.len() <= (max_length as usize),
+            None => true,
+        };
+        let license_is_valid = match &self.license {
+            Some(license) => is_valid_spdx_expression(license),
+            None => true,
+        };
+
+        fits(&self.description, MAX_DESCRIPTION_LENGTH) &&
+            fits(&self.license, MAX_METADATA_FIELD_LENGTH) &&
+            fits(&self.language, MAX_METADATA_FIELD_LENGTH) &&
+            fits(&self.mime_type, MAX_METADATA_FIELD_LENGTH) &&
+            license_is_valid
+    }
+}
+
+/// Where a forked document comes from.
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Provenance {
+    pub parent_doc_id: u32,
+    pub version_id: u32,
+    /// Share of the revenue of the fork paid to the owner of the parent, in basis points.
+    pub royalty: u32,
+}
+
+/// Reference to a document, or to one of its versions when `version_id` is set.
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Citation {
+    pub doc_id: u32,
+    pub version_id: Option<u32>,
+}
+
+/// Comment on a version of a document, or a reply to another comment when `parent_id` is set.
+///
+/// Deleted comments keep their place in threads with an empty body.
+#[derive(scale::Encode, scale::Decode, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Comment {
+    pub id: u32,
+    pub doc_id: u32,
+    pub version_id: u32,
+    pub author: AccountId,
+    pub parent_id: Option<u32>,
+    pub body: String,
+    /// CID of a longer body stored on IPFS.
+    pub ipfs_hash: Option<String>,
+    pub created_at: Timestamp,
+    pub edited_at: Option<Timestamp>,
+    pub deleted: bool,
+}
+
+/// A list of document ids kept by the platform, such as the documents of an owner.
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum DocumentIndex {
+    Owner(AccountId),
+    Contributor(AccountId),
+    State(DocumentState),
+    /// Holds tags in lowercase.
+    Tag(String),
+    /// Documents citing the given document.
+    CitedBy(u32),
+}
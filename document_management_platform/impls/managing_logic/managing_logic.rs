@@ -0,0 +1,3019 @@
+pub use crate::{
+    impls::managing_logic::{ data_structure::*, managing_logic },
+    traits::managing_logic::*,
+};
+pub use managing_logic::Internal as _;
+
+use crate::impls::managing_logic::cid::is_valid_cid;
+use crate::traits::nft_token::{
+    decode_version_token_id,
+    version_attribute_key,
+    version_token_id,
+    NftTokenAttributesRef,
+};
+use ink::{
+    env::{ hash::Blake2x256, hash_encoded },
+    prelude::{ vec, vec::Vec },
+    storage::traits::StorageKey,
+};
+use openbrush::{
+    contracts::traits::{
+        psp22::PSP22Ref,
+        psp37::{
+            Id,
+            PSP37Ref,
+            extensions::{
+                burnable::PSP37BurnableRef,
+                enumerable::PSP37EnumerableRef,
+                metadata::PSP37MetadataRef,
+                mintable::PSP37MintableRef,
+            },
+        },
+    },
+    storage::Mapping,
+    traits::{ AccountId, Balance, DefaultEnv, Storage, String, Timestamp },
+};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub docs: Mapping<u32, NftDocument>,
+    pub versions: Mapping<(u32, u32), Version>,
+    pub next_doc_id: u32,
+    pub index_entries: Mapping<(DocumentIndex, u32), u32>,
+    pub index_lengths: Mapping<DocumentIndex, u32>,
+    pub index_positions: Mapping<(DocumentIndex, u32), u32>,
+    pub rejection_reasons: Mapping<(u32, u32), String>,
+    pub points: Mapping<AccountId, u32>,
+    pub claimed_points: Mapping<AccountId, u32>,
+    pub platform_reviewers: Mapping<AccountId, ()>,
+    pub reward_per_point: Balance,
+    pub revenue_per_share: Mapping<u32, Balance>,
+    pub revenue_per_share_paid: Mapping<(u32, AccountId), Balance>,
+    pub revenue_credited: Mapping<(u32, AccountId), Balance>,
+    pub revenue_reserved: Balance,
+    pub access_terms: Mapping<u32, AccessTerms>,
+    pub licenses: Mapping<(u32, AccountId), License>,
+    pub platform_fee: u32,
+    pub collected_fees: Balance,
+    pub key_envelopes: Mapping<(u32, u32, AccountId), Vec<u8>>,
+    pub metadata: Mapping<u32, DocumentMetadata>,
+    pub provenance: Mapping<u32, Provenance>,
+    pub fork_royalties: Mapping<u32, u32>,
+    pub references: Mapping<(u32, u32, u32), Citation>,
+    pub references_length: Mapping<(u32, u32), u32>,
+    pub cited_by_version: Mapping<(u32, u32), u32>,
+    pub cites: Mapping<(u32, u32, Citation), ()>,
+    pub comments: Mapping<u32, Comment>,
+    pub next_comment_id: u32,
+    pub version_comments: Mapping<(u32, u32, u32), u32>,
+    pub version_comments_length: Mapping<(u32, u32), u32>,
+    pub comment_replies: Mapping<(u32, u32), u32>,
+    pub comment_replies_length: Mapping<u32, u32>,
+    pub admin: AccountId,
+    pub native_token: AccountId,
+    pub nft_token: AccountId,
+    pub _reserved: Option<()>,
+}
+
+impl<T: Storage<Data>> ManagingLogic for T {
+    default fn get_nfts_length(&self) -> Result<u32, Error> {
+        Ok(self.data().next_doc_id)
+    }
+
+    default fn get_nfts_page(&self, offset: u32, limit: u32) -> Result<Vec<NftDocument>, Error> {
+        let docs = self._paginate(self.data().next_doc_id, offset, limit, Some);
+
+        Ok(
+            docs
+                .into_iter()
+                .filter(|doc| self._is_listed(doc))
+                .collect()
+        )
+    }
+
+    default fn get_documents_by_owner(
+        &self,
+        owner: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error> {
+        Ok(self._paginate_index(DocumentIndex::Owner(owner), offset, limit))
+    }
+
+    default fn get_documents_by_owner_length(&self, owner: AccountId) -> Result<u32, Error> {
+        Ok(self._index_length(&DocumentIndex::Owner(owner)))
+    }
+
+    default fn get_documents_by_contributor(
+        &self,
+        contributor: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error> {
+        Ok(self._paginate_index(DocumentIndex::Contributor(contributor), offset, limit))
+    }
+
+    default fn get_documents_by_contributor_length(
+        &self,
+        contributor: AccountId
+    ) -> Result<u32, Error> {
+        Ok(self._index_length(&DocumentIndex::Contributor(contributor)))
+    }
+
+    default fn get_documents_by_state(
+        &self,
+        state: DocumentState,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error> {
+        let docs = self._paginate_index(DocumentIndex::State(state), offset, limit);
+
+        Ok(
+            docs
+                .into_iter()
+                .filter(|doc| self._is_listed(doc))
+                .collect()
+        )
+    }
+
+    default fn get_documents_by_state_length(&self, state: DocumentState) -> Result<u32, Error> {
+        Ok(self._index_length(&DocumentIndex::State(state)))
+    }
+
+    default fn get_documents_by_tag(
+        &self,
+        tag: String,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error> {
+        let docs = self._paginate_index(DocumentIndex::Tag(tag.to_lowercase()), offset, limit);
+
+        Ok(
+            docs
+                .into_iter()
+                .filter(|doc| self._is_listed(doc))
+                .collect()
+        )
+    }
+
+    default fn get_documents_by_tag_length(&self, tag: String) -> Result<u32, Error> {
+        Ok(self._index_length(&DocumentIndex::Tag(tag.to_lowercase())))
+    }
+
+    default fn get_version_doc_length(&self, doc_id: u32) -> Result<u32, Error> {
+        let doc = self._document(doc_id)?;
+
+        Ok(doc.number_upload)
+    }
+
+    default fn get_version(&self, doc_id: u32, version_id: u32) -> Result<Version, Error> {
+        let doc = self._document(doc_id)?;
+        let version = self._version(doc_id, version_id)?;
+
+        Ok(self._redact_version(&doc, version))
+    }
+
+    default fn get_versions(&self, doc_id: u32) -> Result<Vec<Version>, Error> {
+        let doc = self._document(doc_id)?;
+
+        Ok(
+            (0..doc.number_upload)
+                .filter_map(|version_id| self.data().versions.get(&(doc_id, version_id)))
+                .map(|version| self._redact_version(&doc, version))
+                .collect()
+        )
+    }
+
+    default fn get_version_token_id(&self, doc_id: u32, version_id: u32) -> Result<Id, Error> {
+        self._version(doc_id, version_id)?;
+
+        Ok(version_token_id(doc_id, version_id))
+    }
+
+    default fn decode_version_token_id(&self, token_id: Id) -> Result<(u32, u32), Error> {
+        decode_version_token_id(&token_id).ok_or(Error::TokenNotFound)
+    }
+
+    default fn get_version_tokens(
+        &self,
+        account: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Id>, Error> {
+        let nft_token = self.data().nft_token;
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE));
+
+        Ok(
+            (offset..end)
+                .map_while(|index|
+                    PSP37EnumerableRef::owners_token_by_index(&nft_token, account, index as u128)
+                )
+                .filter(|token_id| decode_version_token_id(token_id).is_some())
+                .collect()
+        )
+    }
+
+    default fn get_document(&self, doc_id: u32) -> Result<NftDocument, Error> {
+        let doc = self._document(doc_id)?;
+
+        Ok(self._redact_document(doc))
+    }
+
+    default fn create_document(
+        &mut self,
+        title: String,
+        locator: ContentLocator,
+        total_supply: Option<Balance>,
+        allocations: Vec<(AccountId, Balance)>,
+        visibility: Visibility
+    ) -> Result<NftDocument, Error> {
+        let post_id = self.get_nfts_length()?;
+        let caller = Self::env().caller();
+
+        if !locator.is_valid() {
+            return Err(Error::InvalidContentHash);
+        }
+
+        let nft_token = self.data().nft_token;
+
+        let new_version = Version {
+            version_id: 0,
+            contributor: caller,
+            locator: Some(locator.clone()),
+            state: DocumentState::Publish,
+            mirrors: Vec::new(),
+        };
+
+        let token_id: Id = Id::U32(post_id);
+        let total_supply = total_supply.unwrap_or(DEFAULT_SHARE_SUPPLY);
+        let allocations = if allocations.is_empty() {
+            vec![(caller, total_supply)]
+        } else {
+            allocations
+        };
+
+        let allocations = self._merge_allocations(total_supply, allocations)?;
+        let caller_shares = allocations
+            .iter()
+            .find(|(account, _)| *account == caller)
+            .map_or(0, |(_, amount)| *amount);
+
+        // Ownership goes with a majority of the shares, as in `claim_document_ownership`.
+        if caller_shares <= total_supply / 2 {
+            return Err(Error::InvalidAllocation);
+        }
+
+        // One mint per recipient, the caller's shares going out with the version token.
+        PSP37MintableRef::mint(
+            &nft_token,
+            caller,
+            vec![(version_token_id(post_id, 0), 1), (token_id.clone(), caller_shares)]
+        )?;
+        for (account, amount) in allocations.into_iter().filter(|(account, _)| *account != caller) {
+            PSP37MintableRef::mint(&nft_token, account, vec![(token_id.clone(), amount)])?;
+        }
+
+        let new_doc = NftDocument {
+            id: post_id,
+            owner: caller,
+            title,
+            locator: Some(locator),
+            post_owner: Vec::new(),
+            reviewers: Vec::new(),
+            version_id_publish: 0,
+            number_upload: 1,
+            visibility,
+        };
+
+        self._set_version_attribute(&new_doc, &new_version)?;
+        self._add_nft(new_doc.clone())?;
+        self.data().versions.insert(&(post_id, 0), &new_version);
+        self._index_insert(DocumentIndex::Contributor(caller), post_id);
+        self._index_insert(DocumentIndex::State(DocumentState::Publish), post_id);
+
+        self._emit_version_created_event(
+            post_id,
+            caller,
+            0,
+            self._public_locator(&new_doc, &new_version)
+        );
+        self._emit_version_published_event(post_id, caller, 0);
+
+        Ok(new_doc)
+    }
+
+    default fn fork_document(
+        &mut self,
+        parent_doc_id: u32,
+        version_id: u32,
+        title: String,
+        locator: ContentLocator
+    ) -> Result<NftDocument, Error> {
+        let caller = Self::env().caller();
+        let parent = self._document(parent_doc_id)?;
+        let version = self._version(parent_doc_id, version_id)?;
+
+        if
+            !self._can_view(&parent, version.version_id, caller) ||
+            !self.has_access(parent_doc_id, caller)?
+        {
+            return Err(Error::NotAllowed);
+        }
+
+        let provenance = Provenance {
+            parent_doc_id,
+            version_id,
+            royalty: self.data().fork_royalties.get(&parent_doc_id).unwrap_or(0),
+        };
+        let doc = self.create_document(
+            title,
+            locator,
+            None,
+            Vec::new(),
+            parent.visibility.clone()
+        )?;
+
+        self.data().provenance.insert(&doc.id, &provenance);
+
+        self._emit_document_forked_event(doc.id, parent_doc_id, version_id);
+
+        Ok(doc)
+    }
+
+    default fn get_ancestry(&self, doc_id: u32) -> Result<Vec<Provenance>, Error> {
+        self._document(doc_id)?;
+
+        let mut ancestry = Vec::new();
+        let mut current = doc_id;
+
+        while (ancestry.len() as u32) < MAX_PAGE_SIZE {
+            match self.data().provenance.get(&current) {
+                Some(provenance) => {
+                    current = provenance.parent_doc_id;
+                    ancestry.push(provenance);
+                }
+                None => {
+                    break;
+                }
+            }
+        }
+
+        Ok(ancestry)
+    }
+
+    default fn set_fork_royalty(&mut self, doc_id: u32, royalty: u32) -> Result<(), Error> {
+        self._document_of_owner(doc_id)?;
+
+        if royalty > FEE_DENOMINATOR {
+            return Err(Error::InvalidAmount);
+        }
+
+        self.data().fork_royalties.insert(&doc_id, &royalty);
+
+        Ok(())
+    }
+
+    default fn get_fork_royalty(&self, doc_id: u32) -> Result<u32, Error> {
+        self._document(doc_id)?;
+
+        Ok(self.data().fork_royalties.get(&doc_id).unwrap_or(0))
+    }
+
+    default fn create_version_document(
+        &mut self,
+        doc_id: u32,
+        locator: ContentLocator
+    ) -> Result<Version, Error> {
+        let caller = Self::env().caller();
+        let next_version_id = self.get_version_doc_length(doc_id)?;
+
+        self._ensure_can_create_version(doc_id, caller)?;
+
+        if !locator.is_valid() {
+            return Err(Error::InvalidContentHash);
+        }
+
+        let new_version = Version {
+            version_id: next_version_id,
+            contributor: caller,
+            locator: Some(locator),
+            state: DocumentState::Draft,
+            mirrors: Vec::new(),
+        };
+
+        let nft_token = self.data().nft_token;
+
+        PSP37MintableRef::mint(
+            &nft_token,
+            caller,
+            vec![(version_token_id(doc_id, next_version_id), 1)]
+        )?;
+
+        let mut doc = self._document(doc_id)?;
+
+        self._set_version_attribute(&doc, &new_version)?;
+
+        self.data().versions.insert(&(doc_id, next_version_id), &new_version);
+        doc.number_upload = doc.number_upload.checked_add(1).ok_or(Error::VersionLimitReached)?;
+        self.data().docs.insert(&doc_id, &doc);
+        self._index_insert(DocumentIndex::Contributor(caller), doc_id);
+
+        self._emit_version_created_event(
+            doc_id,
+            caller,
+            next_version_id,
+            self._public_locator(&doc, &new_version)
+        );
+
+        Ok(new_version)
+    }
+
+    default fn retract_document(&mut self, doc_id: u32) -> Result<(), Error> {
+        let doc = self._document_of_owner(doc_id)?;
+        let mut published = self._version(doc_id, doc.version_id_publish)?;
+
+        if published.state == DocumentState::Retracted {
+            return Err(Error::DocumentRetracted);
+        }
+
+        published.state = DocumentState::Retracted;
+        self.data().versions.insert(&(doc_id, published.version_id), &published);
+        self._refresh_version_attributes(&doc)?;
+        self._index_remove(DocumentIndex::State(DocumentState::Publish), doc_id);
+        self._index_insert(DocumentIndex::State(DocumentState::Retracted), doc_id);
+
+        self._emit_document_retracted_event(doc_id);
+
+        Ok(())
+    }
+
+    default fn delete_document(&mut self, doc_id: u32) -> Result<(), Error> {
+        let doc = self._document_of_owner(doc_id)?;
+        let nft_token = self.data().nft_token;
+        let token_id = Id::U32(doc_id);
+
+        let shares = PSP37Ref::balance_of(&nft_token, doc.owner, Some(token_id.clone()));
+        let total_supply = PSP37Ref::total_supply(&nft_token, Some(token_id.clone()));
+
+        if shares != total_supply {
+            return Err(Error::NotAllowed);
+        }
+
+        let versions: Vec<Version> = (0..doc.number_upload)
+            .filter_map(|version_id| self.data().versions.get(&(doc_id, version_id)))
+            .collect();
+
+        // Version tokens passed on by their contributor cannot be found and stay where they are.
+        let mut burns = vec![(doc.owner, vec![(token_id, shares)])];
+
+        for version in versions.iter() {
+            let version_token = version_token_id(doc_id, version.version_id);
+            let contributor = version.contributor;
+
+            if PSP37Ref::balance_of(&nft_token, contributor, Some(version_token.clone())) == 0 {
+                continue;
+            }
+
+            match burns.iter_mut().find(|(account, _)| *account == contributor) {
+                Some((_, ids_amounts)) => ids_amounts.push((version_token, 1)),
+                None => burns.push((contributor, vec![(version_token, 1)])),
+            }
+        }
+
+        self._settle_revenue(doc_id, doc.owner, shares);
+        for (account, ids_amounts) in burns {
+            PSP37BurnableRef::burn(&nft_token, account, ids_amounts)?;
+        }
+
+        let state = if self._is_retracted(&doc) {
+            DocumentState::Retracted
+        } else {
+            DocumentState::Publish
+        };
+        self._index_remove(DocumentIndex::State(state), doc_id);
+        self._index_remove(DocumentIndex::Owner(doc.owner), doc_id);
+
+        for tag in self.data().metadata.get(&doc_id).unwrap_or_default().tags {
+            self._index_remove(DocumentIndex::Tag(tag), doc_id);
+        }
+
+        for version in versions {
+            self._index_remove(DocumentIndex::Contributor(version.contributor), doc_id);
+            self._remove_references(doc_id, version.version_id);
+            self.data().versions.remove(&(doc_id, version.version_id));
+            self.data().rejection_reasons.remove(&(doc_id, version.version_id));
+        }
+
+        self.data().metadata.remove(&doc_id);
+        self.data().access_terms.remove(&doc_id);
+        self.data().fork_royalties.remove(&doc_id);
+        self.data().revenue_per_share.remove(&doc_id);
+        self.data().revenue_per_share_paid.remove(&(doc_id, doc.owner));
+        self.data().docs.remove(&doc_id);
+
+        self._emit_document_deleted_event(doc_id);
+
+        Ok(())
+    }
+
+    default fn set_metadata(
+        &mut self,
+        doc_id: u32,
+        mut metadata: DocumentMetadata
+    ) -> Result<(), Error> {
+        self._document_of_owner(doc_id)?;
+
+        if !metadata.is_valid() {
+            return Err(Error::InvalidMetadata);
+        }
+
+        if metadata.tags.len() > (MAX_TAGS as usize) {
+            return Err(Error::TagLimitReached);
+        }
+
+        let mut tags: Vec<String> = Vec::with_capacity(metadata.tags.len());
+
+        for tag in metadata.tags.iter() {
+            let tag = tag.to_lowercase();
+
+            if tag.is_empty() || tag.len() > (MAX_TAG_LENGTH as usize) || tags.contains(&tag) {
+                return Err(Error::InvalidTag);
+            }
+
+            tags.push(tag);
+        }
+
+        let previous_tags = self.data().metadata.get(&doc_id).unwrap_or_default().tags;
+
+        for tag in previous_tags.iter().filter(|tag| !tags.contains(tag)) {
+            self._index_remove(DocumentIndex::Tag(tag.clone()), doc_id);
+        }
+
+        for tag in tags.iter().filter(|tag| !previous_tags.contains(tag)) {
+            self._index_insert(DocumentIndex::Tag(tag.clone()), doc_id);
+        }
+
+        metadata.tags = tags;
+        self.data().metadata.insert(&doc_id, &metadata);
+
+        self._emit_metadata_updated_event(doc_id);
+
+        Ok(())
+    }
+
+    default fn get_metadata(&self, doc_id: u32) -> Result<DocumentMetadata, Error> {
+        self._document(doc_id)?;
+
+        Ok(self.data().metadata.get(&doc_id).unwrap_or_default())
+    }
+
+    default fn set_visibility(&mut self, doc_id: u32, visibility: Visibility) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+        let was_private = doc.visibility == Visibility::Private;
+
+        doc.visibility = visibility.clone();
+        self.data().docs.insert(&doc_id, &doc);
+
+        if was_private != (visibility == Visibility::Private) {
+            self._refresh_version_attributes(&doc)?;
+        }
+
+        self._emit_visibility_changed_event(doc_id, visibility);
+
+        Ok(())
+    }
+
+    default fn grant_key_envelope(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId,
+        envelope: Vec<u8>
+    ) -> Result<(), Error> {
+        self._document_of_owner(doc_id)?;
+        self._version(doc_id, version_id)?;
+
+        self.data().key_envelopes.insert(&(doc_id, version_id, recipient), &envelope);
+
+        self._emit_key_envelope_granted_event(doc_id, version_id, recipient);
+
+        Ok(())
+    }
+
+    default fn revoke_key_envelope(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId
+    ) -> Result<(), Error> {
+        self._document_of_owner(doc_id)?;
+
+        if !self.data().key_envelopes.contains(&(doc_id, version_id, recipient)) {
+            return Err(Error::KeyEnvelopeNotFound);
+        }
+
+        self.data().key_envelopes.remove(&(doc_id, version_id, recipient));
+
+        self._emit_key_envelope_revoked_event(doc_id, version_id, recipient);
+
+        Ok(())
+    }
+
+    default fn get_key_envelope(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self._version(doc_id, version_id)?;
+
+        Ok(self.data().key_envelopes.get(&(doc_id, version_id, recipient)))
+    }
+
+    default fn submit_version_for_review(
+        &mut self,
+        doc_id: u32,
+        version_id: u32
+    ) -> Result<(), Error> {
+        let mut version = self._version(doc_id, version_id)?;
+
+        if version.contributor != Self::env().caller() {
+            return Err(Error::NotAllowed);
+        }
+
+        if version.state != DocumentState::Draft {
+            return Err(Error::InvalidVersionState);
+        }
+
+        version.state = DocumentState::PendingReview;
+        self.data().versions.insert(&(doc_id, version_id), &version);
+
+        self._emit_version_submitted_event(doc_id, version.contributor, version_id);
+
+        Ok(())
+    }
+
+    default fn approve_version(&mut self, doc_id: u32, version_id: u32) -> Result<(), Error> {
+        let caller = Self::env().caller();
+        let mut doc = self._document_of_reviewer(doc_id)?;
+        let mut version = self._version(doc_id, version_id)?;
+
+        self._ensure_can_sign_off(&doc, &version, caller)?;
+
+        if version.state != DocumentState::PendingReview {
+            return Err(Error::InvalidVersionState);
+        }
+
+        // Owners pick their own reviewers, so only approvals from reviewers the admin vouches
+        // for, on documents they do not own, are worth points.
+        if
+            version.contributor != caller &&
+            doc.owner != caller &&
+            self.data().platform_reviewers.contains(&caller)
+        {
+            self._award_points(&mut doc, version.contributor);
+        }
+
+        self._publish_version(&mut doc, &mut version)
+    }
+
+    default fn reject_version(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        reason: String
+    ) -> Result<(), Error> {
+        self._document_of_reviewer(doc_id)?;
+        let mut version = self._version(doc_id, version_id)?;
+
+        if version.state != DocumentState::PendingReview {
+            return Err(Error::InvalidVersionState);
+        }
+
+        version.state = DocumentState::Rejected;
+        self.data().versions.insert(&(doc_id, version_id), &version);
+        self.data().rejection_reasons.insert(&(doc_id, version_id), &reason);
+
+        self._emit_version_rejected_event(
+            doc_id,
+            version.contributor,
+            Self::env().caller(),
+            version_id,
+            reason
+        );
+
+        Ok(())
+    }
+
+    default fn publish_version(&mut self, doc_id: u32, version_id: u32) -> Result<(), Error> {
+        let mut doc = self._document_of_reviewer(doc_id)?;
+        let mut version = self._version(doc_id, version_id)?;
+
+        self._ensure_can_sign_off(&doc, &version, Self::env().caller())?;
+
+        if version.state != DocumentState::Archived {
+            return Err(Error::InvalidVersionState);
+        }
+
+        self._set_version_attribute(&doc, &version)?;
+
+        self._publish_version(&mut doc, &mut version)
+    }
+
+    default fn add_mirror(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        locator: ContentLocator
+    ) -> Result<(), Error> {
+        let doc = self._document_of_owner(doc_id)?;
+        let mut version = self._version(doc_id, version_id)?;
+
+        if self._is_retracted(&doc) {
+            return Err(Error::DocumentRetracted);
+        }
+
+        if !locator.is_valid() {
+            return Err(Error::InvalidContentHash);
+        }
+
+        if version.locators().contains(&locator) {
+            return Err(Error::MirrorExists);
+        }
+
+        if version.mirrors.len() >= (MAX_MIRRORS as usize) {
+            return Err(Error::MirrorLimitReached);
+        }
+
+        version.mirrors.push(locator.clone());
+        self.data().versions.insert(&(doc_id, version_id), &version);
+        self._set_version_attribute(&doc, &version)?;
+
+        let locator = (doc.visibility != Visibility::Private).then_some(locator);
+        self._emit_mirror_added_event(doc_id, version_id, locator);
+
+        Ok(())
+    }
+
+    default fn get_locators(
+        &self,
+        doc_id: u32,
+        version_id: u32
+    ) -> Result<Vec<ContentLocator>, Error> {
+        let version = self.get_version(doc_id, version_id)?;
+
+        Ok(version.locators())
+    }
+
+    default fn add_citation(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        cited: Citation
+    ) -> Result<(), Error> {
+        self._ensure_can_create_version(doc_id, Self::env().caller())?;
+        let version = self._version(doc_id, version_id)?;
+
+        // Only versions that made it through review can cite, so drafts cannot pad counts.
+        if !matches!(version.state, DocumentState::Publish | DocumentState::Archived) {
+            return Err(Error::InvalidVersionState);
+        }
+
+        if cited.doc_id == doc_id {
+            return Err(Error::NotAllowed);
+        }
+
+        match cited.version_id {
+            Some(cited_version_id) => {
+                self._version(cited.doc_id, cited_version_id)?;
+            }
+            None => {
+                self._document(cited.doc_id)?;
+            }
+        }
+
+        if self.data().cites.contains(&(doc_id, version_id, cited.clone())) {
+            return Err(Error::CitationExists);
+        }
+
+        let length = self.data().references_length.get(&(doc_id, version_id)).unwrap_or(0);
+
+        if length >= MAX_REFERENCES {
+            return Err(Error::ReferenceLimitReached);
+        }
+
+        self.data().references.insert(&(doc_id, version_id, length), &cited);
+        self.data().references_length.insert(&(doc_id, version_id), &(length + 1));
+        self.data().cites.insert(&(doc_id, version_id, cited.clone()), &());
+
+        // A document counts once towards the citations of another, however many of its
+        // versions cite it.
+        if self._index_insert(DocumentIndex::CitedBy(cited.doc_id), doc_id) {
+            self.data().cited_by_version.insert(&(cited.doc_id, doc_id), &version_id);
+        }
+
+        self._emit_citation_added_event(doc_id, version_id, cited);
+
+        Ok(())
+    }
+
+    default fn get_references(
+        &self,
+        doc_id: u32,
+        version_id: u32
+    ) -> Result<Vec<(Citation, Option<DocumentState>)>, Error> {
+        self._version(doc_id, version_id)?;
+        let length = self.data().references_length.get(&(doc_id, version_id)).unwrap_or(0);
+
+        Ok(
+            (0..length)
+                .filter_map(|index| self.data().references.get(&(doc_id, version_id, index)))
+                .map(|cited| {
+                    let cited_version_id = match cited.version_id {
+                        Some(cited_version_id) => Some(cited_version_id),
+                        None =>
+                            self.data()
+                                .docs.get(&cited.doc_id)
+                                .map(|doc| doc.version_id_publish),
+                    };
+                    let state = cited_version_id
+                        .and_then(|cited_version_id|
+                            self.data().versions.get(&(cited.doc_id, cited_version_id))
+                        )
+                        .map(|version| version.state);
+
+                    (cited, state)
+                })
+                .collect()
+        )
+    }
+
+    default fn get_cited_by(
+        &self,
+        doc_id: u32,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Citation>, Error> {
+        let index = DocumentIndex::CitedBy(doc_id);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(self._index_length(&index));
+
+        Ok(
+            (offset..end)
+                .filter_map(|position| self._index_entry(&index, position))
+                .map(|citing_doc_id| Citation {
+                    doc_id: citing_doc_id,
+                    version_id: self.data().cited_by_version.get(&(doc_id, citing_doc_id)),
+                })
+                .collect()
+        )
+    }
+
+    default fn get_citation_count(&self, doc_id: u32) -> Result<u32, Error> {
+        Ok(self._index_length(&DocumentIndex::CitedBy(doc_id)))
+    }
+
+    default fn post_comment(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        body: String,
+        ipfs_hash: Option<String>,
+        parent_id: Option<u32>
+    ) -> Result<Comment, Error> {
+        let caller = Self::env().caller();
+
+        self._ensure_can_comment(doc_id, version_id, caller)?;
+        self._ensure_valid_comment(&body, &ipfs_hash)?;
+
+        if let Some(parent_id) = parent_id {
+            let parent = self._comment(parent_id)?;
+
+            if parent.doc_id != doc_id || parent.version_id != version_id || parent.deleted {
+                return Err(Error::CommentNotFound);
+            }
+        }
+
+        let comment_id = self.data().next_comment_id;
+        let comment = Comment {
+            id: comment_id,
+            doc_id,
+            version_id,
+            author: caller,
+            parent_id,
+            body,
+            ipfs_hash,
+            created_at: Self::env().block_timestamp(),
+            edited_at: None,
+            deleted: false,
+        };
+
+        self.data().next_comment_id = comment_id.checked_add(1).ok_or(Error::CannotInsert)?;
+        self.data().comments.insert(&comment_id, &comment);
+
+        match parent_id {
+            Some(parent_id) => {
+                let length = self.data().comment_replies_length.get(&parent_id).unwrap_or(0);
+
+                self.data().comment_replies.insert(&(parent_id, length), &comment_id);
+                self.data().comment_replies_length.insert(&parent_id, &(length + 1));
+            }
+            None => {
+                let length = self.get_comments_length(doc_id, version_id)?;
+
+                self.data().version_comments.insert(&(doc_id, version_id, length), &comment_id);
+                self.data().version_comments_length.insert(&(doc_id, version_id), &(length + 1));
+            }
+        }
+
+        self._emit_comment_posted_event(comment_id, doc_id, version_id, caller, parent_id);
+
+        Ok(comment)
+    }
+
+    default fn edit_comment(
+        &mut self,
+        comment_id: u32,
+        body: String,
+        ipfs_hash: Option<String>
+    ) -> Result<(), Error> {
+        let mut comment = self._comment(comment_id)?;
+
+        if comment.author != Self::env().caller() {
+            return Err(Error::NotAllowed);
+        }
+
+        if comment.deleted {
+            return Err(Error::CommentNotFound);
+        }
+
+        self._ensure_valid_comment(&body, &ipfs_hash)?;
+
+        comment.body = body;
+        comment.ipfs_hash = ipfs_hash;
+        comment.edited_at = Some(Self::env().block_timestamp());
+        self.data().comments.insert(&comment_id, &comment);
+
+        self._emit_comment_edited_event(comment_id, comment.doc_id);
+
+        Ok(())
+    }
+
+    default fn delete_comment(&mut self, comment_id: u32) -> Result<(), Error> {
+        let caller = Self::env().caller();
+        let mut comment = self._comment(comment_id)?;
+
+        if comment.deleted {
+            return Err(Error::CommentNotFound);
+        }
+
+        if comment.author != caller && self._document(comment.doc_id)?.owner != caller {
+            return Err(Error::NotAllowed);
+        }
+
+        comment.body = String::new();
+        comment.ipfs_hash = None;
+        comment.deleted = true;
+        self.data().comments.insert(&comment_id, &comment);
+
+        self._emit_comment_deleted_event(comment_id, comment.doc_id, caller);
+
+        Ok(())
+    }
+
+    default fn get_comment(&self, comment_id: u32) -> Result<Comment, Error> {
+        let comment = self._comment(comment_id)?;
+
+        self._ensure_can_comment(comment.doc_id, comment.version_id, Self::env().caller())?;
+
+        Ok(comment)
+    }
+
+    default fn get_comments(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Comment>, Error> {
+        self._ensure_can_comment(doc_id, version_id, Self::env().caller())?;
+        let length = self.get_comments_length(doc_id, version_id)?;
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(length);
+
+        Ok(
+            (offset..end)
+                .filter_map(|index| self.data().version_comments.get(&(doc_id, version_id, index)))
+                .filter_map(|comment_id| self.data().comments.get(&comment_id))
+                .collect()
+        )
+    }
+
+    default fn get_comments_length(&self, doc_id: u32, version_id: u32) -> Result<u32, Error> {
+        Ok(self.data().version_comments_length.get(&(doc_id, version_id)).unwrap_or(0))
+    }
+
+    default fn get_replies(
+        &self,
+        comment_id: u32,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Comment>, Error> {
+        let comment = self._comment(comment_id)?;
+
+        self._ensure_can_comment(comment.doc_id, comment.version_id, Self::env().caller())?;
+        let length = self.get_replies_length(comment_id)?;
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(length);
+
+        Ok(
+            (offset..end)
+                .filter_map(|index| self.data().comment_replies.get(&(comment_id, index)))
+                .filter_map(|reply_id| self.data().comments.get(&reply_id))
+                .collect()
+        )
+    }
+
+    default fn get_replies_length(&self, comment_id: u32) -> Result<u32, Error> {
+        Ok(self.data().comment_replies_length.get(&comment_id).unwrap_or(0))
+    }
+
+    default fn get_rejection_reason(
+        &self,
+        doc_id: u32,
+        version_id: u32
+    ) -> Result<Option<String>, Error> {
+        self._version(doc_id, version_id)?;
+
+        Ok(self.data().rejection_reasons.get(&(doc_id, version_id)))
+    }
+
+    default fn add_reviewer(&mut self, doc_id: u32, reviewer: AccountId) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+
+        if reviewer == doc.owner {
+            return Err(Error::NotAllowed);
+        }
+
+        if doc.reviewers.contains(&reviewer) {
+            return Err(Error::ReviewerExists);
+        }
+
+        doc.reviewers.push(reviewer);
+        self.data().docs.insert(&doc_id, &doc);
+
+        self._emit_reviewer_added_event(doc_id, reviewer);
+
+        Ok(())
+    }
+
+    default fn remove_reviewer(&mut self, doc_id: u32, reviewer: AccountId) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+
+        let index = doc.reviewers
+            .iter()
+            .position(|account| *account == reviewer)
+            .ok_or(Error::ReviewerNotFound)?;
+
+        doc.reviewers.swap_remove(index);
+        self.data().docs.insert(&doc_id, &doc);
+
+        self._emit_reviewer_removed_event(doc_id, reviewer);
+
+        Ok(())
+    }
+
+    default fn add_collaborator(
+        &mut self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    ) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+
+        if collaborator == doc.owner {
+            return Err(Error::NotAllowed);
+        }
+
+        if doc.post_owner.iter().any(|post_owner| post_owner.user == collaborator) {
+            return Err(Error::CollaboratorExists);
+        }
+
+        doc.post_owner.push(PostOwner {
+            user: collaborator,
+            user_role: user_role.clone(),
+            point: 0,
+        });
+        self.data().docs.insert(&doc_id, &doc);
+
+        self._emit_collaborator_added_event(doc_id, collaborator, user_role);
+
+        Ok(())
+    }
+
+    default fn remove_collaborator(
+        &mut self,
+        doc_id: u32,
+        collaborator: AccountId
+    ) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+
+        let index = doc.post_owner
+            .iter()
+            .position(|post_owner| post_owner.user == collaborator)
+            .ok_or(Error::CollaboratorNotFound)?;
+
+        doc.post_owner.swap_remove(index);
+        self.data().docs.insert(&doc_id, &doc);
+
+        self._emit_collaborator_removed_event(doc_id, collaborator);
+
+        Ok(())
+    }
+
+    default fn set_collaborator_role(
+        &mut self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    ) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+
+        doc.post_owner
+            .iter_mut()
+            .find(|post_owner| post_owner.user == collaborator)
+            .ok_or(Error::CollaboratorNotFound)?.user_role = user_role.clone();
+        self.data().docs.insert(&doc_id, &doc);
+
+        self._emit_collaborator_role_changed_event(doc_id, collaborator, user_role);
+
+        Ok(())
+    }
+
+    default fn transfer_document(&mut self, doc_id: u32, new_owner: AccountId) -> Result<(), Error> {
+        let mut doc = self._document_of_owner(doc_id)?;
+        let nft_token = self.data().nft_token;
+
+        if new_owner == doc.owner {
+            return Err(Error::NotAllowed);
+        }
+
+        let token_id = Id::U32(doc_id);
+        let shares = PSP37Ref::balance_of(&nft_token, doc.owner, Some(token_id.clone()));
+        let new_owner_shares = PSP37Ref::balance_of(&nft_token, new_owner, Some(token_id.clone()));
+
+        self._settle_revenue(doc_id, doc.owner, shares);
+        self._settle_revenue(doc_id, new_owner, new_owner_shares);
+
+        PSP37Ref::transfer_from(&nft_token, doc.owner, new_owner, token_id, shares, Vec::new())?;
+
+        self._set_document_owner(&mut doc, new_owner);
+
+        Ok(())
+    }
+
+    default fn claim_document_ownership(&mut self, doc_id: u32) -> Result<(), Error> {
+        let caller = Self::env().caller();
+        let mut doc = self._document(doc_id)?;
+        let nft_token = self.data().nft_token;
+
+        if doc.owner == caller {
+            return Err(Error::NotAllowed);
+        }
+
+        let token_id = Id::U32(doc_id);
+        let shares = PSP37Ref::balance_of(&nft_token, caller, Some(token_id.clone()));
+        let total_supply = PSP37Ref::total_supply(&nft_token, Some(token_id));
+
+        if shares <= total_supply / 2 {
+            return Err(Error::NotAllowed);
+        }
+
+        self._set_document_owner(&mut doc, caller);
+
+        Ok(())
+    }
+
+    default fn get_points(&self, account: AccountId) -> Result<u32, Error> {
+        Ok(self.data().points.get(&account).unwrap_or(0))
+    }
+
+    default fn get_claimable_rewards(&self, account: AccountId) -> Result<Balance, Error> {
+        let unclaimed = self._unclaimed_points(account);
+
+        Ok((unclaimed as Balance).saturating_mul(self.data().reward_per_point))
+    }
+
+    default fn claim_rewards(&mut self) -> Result<Balance, Error> {
+        let caller = Self::env().caller();
+        let native_token = self.data().native_token;
+        let unclaimed = self._unclaimed_points(caller);
+        let amount = self.get_claimable_rewards(caller)?;
+
+        if amount == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        // Payments waiting to be claimed by shareholders and platform fees waiting to be
+        // withdrawn are not part of the reward pool.
+        let pool = PSP22Ref::balance_of(&native_token, Self::env().account_id())
+            .saturating_sub(self.data().revenue_reserved)
+            .saturating_sub(self.data().collected_fees);
+
+        if pool < amount {
+            return Err(Error::InsufficientRewardPool);
+        }
+
+        let points = self.data().points.get(&caller).unwrap_or(0);
+        self.data().claimed_points.insert(&caller, &points);
+
+        PSP22Ref::transfer(&native_token, caller, amount, Vec::new()).map_err(
+            |_| Error::TransferFailed
+        )?;
+
+        self._emit_rewards_claimed_event(caller, unclaimed, amount);
+
+        Ok(amount)
+    }
+
+    default fn set_reward_per_point(&mut self, reward_per_point: Balance) -> Result<(), Error> {
+        self._ensure_admin()?;
+
+        self.data().reward_per_point = reward_per_point;
+
+        Ok(())
+    }
+
+    default fn add_platform_reviewer(&mut self, reviewer: AccountId) -> Result<(), Error> {
+        self._ensure_admin()?;
+
+        if self.data().platform_reviewers.contains(&reviewer) {
+            return Err(Error::ReviewerExists);
+        }
+
+        self.data().platform_reviewers.insert(&reviewer, &());
+
+        self._emit_platform_reviewer_added_event(reviewer);
+
+        Ok(())
+    }
+
+    default fn remove_platform_reviewer(&mut self, reviewer: AccountId) -> Result<(), Error> {
+        self._ensure_admin()?;
+
+        if !self.data().platform_reviewers.contains(&reviewer) {
+            return Err(Error::ReviewerNotFound);
+        }
+
+        self.data().platform_reviewers.remove(&reviewer);
+
+        self._emit_platform_reviewer_removed_event(reviewer);
+
+        Ok(())
+    }
+
+    default fn is_platform_reviewer(&self, account: AccountId) -> Result<bool, Error> {
+        Ok(self.data().platform_reviewers.contains(&account))
+    }
+
+    default fn pay_document(&mut self, doc_id: u32, amount: Balance) -> Result<(), Error> {
+        self._document(doc_id)?;
+        let caller = Self::env().caller();
+        let native_token = self.data().native_token;
+
+        if amount == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let total_supply = PSP37Ref::total_supply(&self.data().nft_token, Some(Id::U32(doc_id)));
+
+        if total_supply == 0 {
+            return Err(Error::NotAllowed);
+        }
+
+        let royalty = self._royalty(doc_id, amount).map_or(0, |(_, royalty)| royalty);
+        let shared = amount - royalty;
+        let increase = revenue_per_share_increase(shared, total_supply).ok_or(
+            Error::InvalidAmount
+        )?;
+
+        // Too small a payment would be kept without crediting any holder.
+        if increase == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        PSP22Ref::transfer_from(
+            &native_token,
+            caller,
+            Self::env().account_id(),
+            amount,
+            Vec::new()
+        ).map_err(|_| Error::TransferFailed)?;
+
+        self._pay_royalty(doc_id, amount)?;
+        let revenue_per_share = self.data().revenue_per_share.get(&doc_id).unwrap_or(0);
+
+        self.data().revenue_per_share.insert(&doc_id, &revenue_per_share.saturating_add(increase));
+        self.data().revenue_reserved = self.data().revenue_reserved.saturating_add(shared);
+
+        self._emit_document_paid_event(doc_id, caller, amount);
+
+        Ok(())
+    }
+
+    default fn get_claimable_revenue(
+        &self,
+        doc_id: u32,
+        account: AccountId
+    ) -> Result<Balance, Error> {
+        let shares = PSP37Ref::balance_of(&self.data().nft_token, account, Some(Id::U32(doc_id)));
+
+        Ok(
+            self.data()
+                .revenue_credited.get(&(doc_id, account))
+                .unwrap_or(0)
+                .saturating_add(self._pending_revenue(doc_id, account, shares))
+        )
+    }
+
+    default fn claim_revenue(&mut self, doc_id: u32) -> Result<Balance, Error> {
+        let caller = Self::env().caller();
+        let native_token = self.data().native_token;
+        let shares = PSP37Ref::balance_of(&self.data().nft_token, caller, Some(Id::U32(doc_id)));
+
+        self._settle_revenue(doc_id, caller, shares);
+        let amount = self.data().revenue_credited.get(&(doc_id, caller)).unwrap_or(0);
+
+        if amount == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        self.data().revenue_credited.remove(&(doc_id, caller));
+        self.data().revenue_reserved = self.data().revenue_reserved.saturating_sub(amount);
+
+        PSP22Ref::transfer(&native_token, caller, amount, Vec::new()).map_err(
+            |_| Error::TransferFailed
+        )?;
+
+        self._emit_revenue_claimed_event(doc_id, caller, amount);
+
+        Ok(amount)
+    }
+
+    default fn on_shares_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        token_id: Id,
+        amount: Balance,
+        from_balance: Balance,
+        to_balance: Balance,
+        total_supply: Balance
+    ) -> Result<(), Error> {
+        if Self::env().caller() != self.data().nft_token {
+            return Err(Error::NotAllowed);
+        }
+
+        let doc_id = match token_id {
+            Id::U32(doc_id) => doc_id,
+            _ => {
+                return Ok(());
+            }
+        };
+
+        let half = total_supply / 2;
+        let gives_up_majority = from_balance > half && from_balance.saturating_sub(amount) <= half;
+        let new_owner = match self.data().docs.get(&doc_id) {
+            Some(doc) if doc.owner != to && to_balance.saturating_add(amount) > half => Some(doc),
+            Some(doc) if doc.owner == from && gives_up_majority => {
+                return Err(Error::NotAllowed);
+            }
+            _ => None,
+        };
+
+        self._settle_revenue(doc_id, from, from_balance);
+        self._settle_revenue(doc_id, to, to_balance);
+
+        if let Some(mut doc) = new_owner {
+            self._set_document_owner(&mut doc, to);
+        }
+
+        Ok(())
+    }
+
+    default fn set_access_terms(
+        &mut self,
+        doc_id: u32,
+        terms: Option<AccessTerms>
+    ) -> Result<(), Error> {
+        let doc = self._document_of_owner(doc_id)?;
+
+        if self._is_retracted(&doc) {
+            return Err(Error::DocumentRetracted);
+        }
+
+        if let Some(AccessTerms { duration: Some(0), .. }) = terms {
+            return Err(Error::InvalidAmount);
+        }
+
+        match terms {
+            Some(terms) => self.data().access_terms.insert(&doc_id, &terms),
+            None => self.data().access_terms.remove(&doc_id),
+        }
+
+        Ok(())
+    }
+
+    default fn get_access_terms(&self, doc_id: u32) -> Result<Option<AccessTerms>, Error> {
+        self._document(doc_id)?;
+
+        Ok(self.data().access_terms.get(&doc_id))
+    }
+
+    default fn buy_access(&mut self, doc_id: u32) -> Result<License, Error> {
+        let doc = self._document(doc_id)?;
+        let caller = Self::env().caller();
+        let now = Self::env().block_timestamp();
+        let native_token = self.data().native_token;
+
+        if self._is_retracted(&doc) {
+            return Err(Error::DocumentRetracted);
+        }
+
+        let terms = self.data().access_terms.get(&doc_id).ok_or(Error::NotForSale)?;
+
+        let current_expiry = match self.data().licenses.get(&(doc_id, caller)) {
+            Some(License { expires_at: None }) => {
+                return Err(Error::NotAllowed);
+            }
+            Some(License { expires_at }) => expires_at,
+            None => None,
+        };
+        let expires_at = terms.duration.map(|duration|
+            current_expiry.unwrap_or(now).max(now).saturating_add(duration)
+        );
+        let license = License { expires_at };
+
+        let fee = terms.price.saturating_mul(self.data().platform_fee as Balance) /
+            (FEE_DENOMINATOR as Balance);
+
+        PSP22Ref::transfer_from(
+            &native_token,
+            caller,
+            Self::env().account_id(),
+            terms.price,
+            Vec::new()
+        ).map_err(|_| Error::TransferFailed)?;
+
+        let proceeds = terms.price - fee;
+        let royalty = self._pay_royalty(doc_id, proceeds)?;
+
+        PSP22Ref::transfer(&native_token, doc.owner, proceeds - royalty, Vec::new()).map_err(
+            |_| Error::TransferFailed
+        )?;
+
+        self.data().licenses.insert(&(doc_id, caller), &license);
+        self.data().collected_fees = self.data().collected_fees.saturating_add(fee);
+
+        self._emit_access_purchased_event(doc_id, caller, terms.price, expires_at);
+
+        Ok(license)
+    }
+
+    default fn get_license(
+        &self,
+        doc_id: u32,
+        account: AccountId
+    ) -> Result<Option<License>, Error> {
+        self._document(doc_id)?;
+
+        Ok(self.data().licenses.get(&(doc_id, account)))
+    }
+
+    default fn has_access(&self, doc_id: u32, account: AccountId) -> Result<bool, Error> {
+        let doc = self._document(doc_id)?;
+
+        if !self.data().access_terms.contains(&doc_id) || self._is_member(&doc, account) {
+            return Ok(true);
+        }
+
+        Ok(
+            match self.data().licenses.get(&(doc_id, account)) {
+                Some(License { expires_at: None }) => true,
+                Some(License { expires_at: Some(expires_at) }) =>
+                    expires_at > Self::env().block_timestamp(),
+                None => false,
+            }
+        )
+    }
+
+    default fn set_platform_fee(&mut self, platform_fee: u32) -> Result<(), Error> {
+        self._ensure_admin()?;
+
+        if platform_fee > FEE_DENOMINATOR {
+            return Err(Error::InvalidAmount);
+        }
+
+        self.data().platform_fee = platform_fee;
+
+        Ok(())
+    }
+
+    default fn get_platform_fee(&self) -> Result<u32, Error> {
+        Ok(self.data().platform_fee)
+    }
+
+    default fn get_collected_fees(&self) -> Result<Balance, Error> {
+        Ok(self.data().collected_fees)
+    }
+
+    default fn withdraw_collected_fees(&mut self, to: AccountId) -> Result<Balance, Error> {
+        self._ensure_admin()?;
+        let amount = self.data().collected_fees;
+
+        if amount == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        self.data().collected_fees = 0;
+
+        PSP22Ref::transfer(&self.data().native_token, to, amount, Vec::new()).map_err(
+            |_| Error::TransferFailed
+        )?;
+
+        self._emit_fees_withdrawn_event(to, amount);
+
+        Ok(amount)
+    }
+
+    default fn migrate_documents(&mut self, doc_ids: Vec<u32>) -> Result<(), Error> {
+        self._ensure_admin()?;
+
+        for doc_id in doc_ids {
+            self._migrate_document(doc_id)?;
+        }
+
+        Ok(())
+    }
+
+    default fn get_total_supply(&self, token_id: Option<Id>) -> Result<Balance, Error> {
+        Ok(PSP37Ref::total_supply(&self.data().nft_token, token_id))
+    }
+
+    default fn get_attribute_version(
+        &self,
+        token_id: Id,
+        version_id: u32
+    ) -> Result<Option<Vec<u8>>, Error> {
+        Ok(
+            PSP37MetadataRef::get_attribute(
+                &self.data().nft_token,
+                token_id,
+                version_attribute_key(version_id)
+            )
+        )
+    }
+}
+
+pub trait Internal {
+    /// User must override those methods in their contract.
+    fn _emit_document_created_event(&self, doc_id: u32, owner: AccountId, title: String);
+    fn _emit_version_created_event(
+        &self,
+        doc_id: u32,
+        contributor: AccountId,
+        version_id: u32,
+        locator: Option<ContentLocator>
+    );
+    fn _emit_version_published_event(&self, doc_id: u32, contributor: AccountId, version_id: u32);
+    fn _emit_version_archived_event(&self, doc_id: u32, contributor: AccountId, version_id: u32);
+    fn _emit_version_submitted_event(&self, doc_id: u32, contributor: AccountId, version_id: u32);
+    fn _emit_version_rejected_event(
+        &self,
+        doc_id: u32,
+        contributor: AccountId,
+        reviewer: AccountId,
+        version_id: u32,
+        reason: String
+    );
+    fn _emit_document_ownership_transferred_event(
+        &self,
+        doc_id: u32,
+        from: AccountId,
+        to: AccountId
+    );
+    fn _emit_collaborator_added_event(
+        &self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    );
+    fn _emit_collaborator_role_changed_event(
+        &self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    );
+    fn _emit_collaborator_removed_event(&self, doc_id: u32, collaborator: AccountId);
+    fn _emit_reviewer_added_event(&self, doc_id: u32, reviewer: AccountId);
+    fn _emit_reviewer_removed_event(&self, doc_id: u32, reviewer: AccountId);
+    fn _emit_rewards_claimed_event(&self, account: AccountId, points: u32, amount: Balance);
+    fn _emit_platform_reviewer_added_event(&self, reviewer: AccountId);
+    fn _emit_platform_reviewer_removed_event(&self, reviewer: AccountId);
+    fn _emit_document_paid_event(&self, doc_id: u32, payer: AccountId, amount: Balance);
+    fn _emit_revenue_claimed_event(&self, doc_id: u32, account: AccountId, amount: Balance);
+    fn _emit_visibility_changed_event(&self, doc_id: u32, visibility: Visibility);
+    fn _emit_key_envelope_granted_event(&self, doc_id: u32, version_id: u32, recipient: AccountId);
+    fn _emit_key_envelope_revoked_event(&self, doc_id: u32, version_id: u32, recipient: AccountId);
+    fn _emit_document_retracted_event(&self, doc_id: u32);
+    fn _emit_document_deleted_event(&self, doc_id: u32);
+    fn _emit_metadata_updated_event(&self, doc_id: u32);
+    fn _emit_comment_posted_event(
+        &self,
+        comment_id: u32,
+        doc_id: u32,
+        version_id: u32,
+        author: AccountId,
+        parent_id: Option<u32>
+    );
+    fn _emit_comment_edited_event(&self, comment_id: u32, doc_id: u32);
+    fn _emit_comment_deleted_event(&self, comment_id: u32, doc_id: u32, by: AccountId);
+    fn _emit_citation_added_event(&self, doc_id: u32, version_id: u32, cited: Citation);
+    fn _emit_document_forked_event(&self, doc_id: u32, parent_doc_id: u32, version_id: u32);
+    fn _emit_royalty_paid_event(
+        &self,
+        doc_id: u32,
+        parent_doc_id: u32,
+        recipient: AccountId,
+        amount: Balance
+    );
+    fn _emit_mirror_added_event(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        locator: Option<ContentLocator>
+    );
+    fn _emit_access_purchased_event(
+        &self,
+        doc_id: u32,
+        buyer: AccountId,
+        price: Balance,
+        expires_at: Option<Timestamp>
+    );
+    fn _emit_fees_withdrawn_event(&self, to: AccountId, amount: Balance);
+
+    fn _document(&self, doc_id: u32) -> Result<NftDocument, Error>;
+
+    fn _version(&self, doc_id: u32, version_id: u32) -> Result<Version, Error>;
+
+    fn _comment(&self, comment_id: u32) -> Result<Comment, Error>;
+
+    /// Comments on a version are open to whoever may see its content hash.
+    fn _ensure_can_comment(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        account: AccountId
+    ) -> Result<(), Error>;
+
+    fn _ensure_valid_comment(&self, body: &str, ipfs_hash: &Option<String>) -> Result<(), Error>;
+
+    /// Returns the document if the caller is its owner.
+    fn _document_of_owner(&self, doc_id: u32) -> Result<NftDocument, Error>;
+
+    /// Returns the document if the caller is its owner or one of its reviewers.
+    fn _document_of_reviewer(&self, doc_id: u32) -> Result<NftDocument, Error>;
+
+    /// Contributors cannot sign off their own versions, except the owner of a document without
+    /// reviewers, who has nobody else to ask.
+    fn _ensure_can_sign_off(
+        &self,
+        doc: &NftDocument,
+        version: &Version,
+        account: AccountId
+    ) -> Result<(), Error>;
+
+    fn _ensure_admin(&self) -> Result<(), Error>;
+
+    /// Stores a new document under the next free id.
+    fn _add_nft(&mut self, nft: NftDocument) -> Result<(), Error>;
+
+    /// Rewrites a document stored with `u8` version ids, unless it is already migrated.
+    fn _migrate_document(&mut self, doc_id: u32) -> Result<(), Error>;
+
+    /// Whether `account` owns, collaborates on or reviews `doc`.
+    fn _is_member(&self, doc: &NftDocument, account: AccountId) -> bool;
+
+    /// Whether the owner of `doc` has retracted it.
+    fn _is_retracted(&self, doc: &NftDocument) -> bool;
+
+    /// Whether `doc` shows up in the platform-wide listings.
+    fn _is_listed(&self, doc: &NftDocument) -> bool;
+
+    /// Whether `account` may see the content hash of a version of `doc`.
+    fn _can_view(&self, doc: &NftDocument, version_id: u32, account: AccountId) -> bool;
+
+    /// Clears the content hash of `doc` when the caller may not see it.
+    fn _redact_document(&self, doc: NftDocument) -> NftDocument;
+
+    /// Clears the content hash of `version` when the caller may not see it.
+    fn _redact_version(&self, doc: &NftDocument, version: Version) -> Version;
+
+    /// Checks that share allocations are non-zero and add up exactly to the total supply, and
+    /// adds up the amounts allocated to the same account.
+    fn _merge_allocations(
+        &self,
+        total_supply: Balance,
+        allocations: Vec<(AccountId, Balance)>
+    ) -> Result<Vec<(AccountId, Balance)>, Error>;
+
+    /// Only the owner or a collaborator whose role allows it may upload versions.
+    fn _ensure_can_create_version(&self, doc_id: u32, account: AccountId) -> Result<(), Error>;
+
+    /// The primary locator of `version` as events may publish it: left out for private and
+    /// retracted documents.
+    fn _public_locator(&self, doc: &NftDocument, version: &Version) -> Option<ContentLocator>;
+
+    /// Writes the SCALE-encoded locators of `version` to its attribute on the document token,
+    /// or only their blake2-256 digest when `doc` is private or retracted.
+    fn _set_version_attribute(&self, doc: &NftDocument, version: &Version) -> Result<(), Error>;
+
+    /// Rewrites the attributes of every version of `doc`, after a change to who may read them.
+    fn _refresh_version_attributes(&self, doc: &NftDocument) -> Result<(), Error>;
+
+    /// Archives the currently published version of `doc` and publishes `version` in its place.
+    fn _publish_version(&mut self, doc: &mut NftDocument, version: &mut Version) -> Result<(), Error>;
+
+    /// Collects the documents at positions `offset..offset + limit` of an index of `length` entries.
+    fn _paginate<F>(&self, length: u32, offset: u32, limit: u32, doc_at: F) -> Vec<NftDocument>
+        where F: Fn(u32) -> Option<u32>;
+
+    /// Collects the documents at positions `offset..offset + limit` of `index`.
+    fn _paginate_index(&self, index: DocumentIndex, offset: u32, limit: u32) -> Vec<NftDocument>;
+
+    /// Credits `contributor` for a version that just became the published version of `doc`.
+    fn _award_points(&mut self, doc: &mut NftDocument, contributor: AccountId);
+
+    fn _unclaimed_points(&self, account: AccountId) -> u32;
+
+    /// Revenue `account` earned with `shares` since its last settlement.
+    fn _pending_revenue(&self, doc_id: u32, account: AccountId, shares: Balance) -> Balance;
+
+    /// Credits the pending revenue of `account`; must run before its share balance changes.
+    fn _settle_revenue(&mut self, doc_id: u32, account: AccountId, shares: Balance);
+
+    /// Returns the parent of a fork along with its royalty on `amount`, if there is one to pay.
+    fn _royalty(&self, doc_id: u32, amount: Balance) -> Option<(NftDocument, Balance)>;
+
+    /// Sends the parent of a fork its royalty on `amount`, which this contract already holds,
+    /// and returns the royalty paid.
+    fn _pay_royalty(&mut self, doc_id: u32, amount: Balance) -> Result<Balance, Error>;
+
+    fn _set_document_owner(&mut self, doc: &mut NftDocument, new_owner: AccountId);
+
+    /// Clears the references declared by a version and takes them out of the counts of the
+    /// documents they cite.
+    fn _remove_references(&mut self, doc_id: u32, version_id: u32);
+
+    fn _index_length(&self, index: &DocumentIndex) -> u32;
+
+    fn _index_entry(&self, index: &DocumentIndex, position: u32) -> Option<u32>;
+
+    /// Appends `doc_id` to `index`; returns whether it was not there yet.
+    fn _index_insert(&mut self, index: DocumentIndex, doc_id: u32) -> bool;
+
+    /// Removes `doc_id` from `index` by swapping the last entry into its slot.
+    fn _index_remove(&mut self, index: DocumentIndex, doc_id: u32);
+}
+
+impl<T: Storage<Data>> Internal for T {
+    default fn _emit_document_created_event(&self, _doc_id: u32, _owner: AccountId, _title: String) {}
+
+    default fn _emit_version_created_event(
+        &self,
+        _doc_id: u32,
+        _contributor: AccountId,
+        _version_id: u32,
+        _locator: Option<ContentLocator>
+    ) {}
+
+    default fn _emit_version_published_event(
+        &self,
+        _doc_id: u32,
+        _contributor: AccountId,
+        _version_id: u32
+    ) {}
+
+    default fn _emit_version_archived_event(
+        &self,
+        _doc_id: u32,
+        _contributor: AccountId,
+        _version_id: u32
+    ) {}
+
+    default fn _emit_version_submitted_event(
+        &self,
+        _doc_id: u32,
+        _contributor: AccountId,
+        _version_id: u32
+    ) {}
+
+    default fn _emit_version_rejected_event(
+        &self,
+        _doc_id: u32,
+        _contributor: AccountId,
+        _reviewer: AccountId,
+        _version_id: u32,
+        _reason: String
+    ) {}
+
+    default fn _emit_document_ownership_transferred_event(
+        &self,
+        _doc_id: u32,
+        _from: AccountId,
+        _to: AccountId
+    ) {}
+
+    default fn _emit_collaborator_added_event(
+        &self,
+        _doc_id: u32,
+        _collaborator: AccountId,
+        _user_role: UserRole
+    ) {}
+
+    default fn _emit_collaborator_role_changed_event(
+        &self,
+        _doc_id: u32,
+        _collaborator: AccountId,
+        _user_role: UserRole
+    ) {}
+
+    default fn _emit_collaborator_removed_event(&self, _doc_id: u32, _collaborator: AccountId) {}
+
+    default fn _emit_reviewer_added_event(&self, _doc_id: u32, _reviewer: AccountId) {}
+
+    default fn _emit_reviewer_removed_event(&self, _doc_id: u32, _reviewer: AccountId) {}
+
+    default fn _emit_rewards_claimed_event(
+        &self,
+        _account: AccountId,
+        _points: u32,
+        _amount: Balance
+    ) {}
+
+    default fn _emit_platform_reviewer_added_event(&self, _reviewer: AccountId) {}
+
+    default fn _emit_platform_reviewer_removed_event(&self, _reviewer: AccountId) {}
+
+    default fn _emit_document_paid_event(
+        &self,
+        _doc_id: u32,
+        _payer: AccountId,
+        _amount: Balance
+    ) {}
+
+    default fn _emit_revenue_claimed_event(
+        &self,
+        _doc_id: u32,
+        _account: AccountId,
+        _amount: Balance
+    ) {}
+
+    default fn _emit_visibility_changed_event(&self, _doc_id: u32, _visibility: Visibility) {}
+
+    default fn _emit_key_envelope_granted_event(
+        &self,
+        _doc_id: u32,
+        _version_id: u32,
+        _recipient: AccountId
+    ) {}
+
+    default fn _emit_key_envelope_revoked_event(
+        &self,
+        _doc_id: u32,
+        _version_id: u32,
+        _recipient: AccountId
+    ) {}
+
+    default fn _emit_document_retracted_event(&self, _doc_id: u32) {}
+
+    default fn _emit_document_deleted_event(&self, _doc_id: u32) {}
+
+    default fn _emit_metadata_updated_event(&self, _doc_id: u32) {}
+
+    default fn _emit_comment_posted_event(
+        &self,
+        _comment_id: u32,
+        _doc_id: u32,
+        _version_id: u32,
+        _author: AccountId,
+        _parent_id: Option<u32>
+    ) {}
+
+    default fn _emit_comment_edited_event(&self, _comment_id: u32, _doc_id: u32) {}
+
+    default fn _emit_comment_deleted_event(&self, _comment_id: u32, _doc_id: u32, _by: AccountId) {}
+
+    default fn _emit_citation_added_event(
+        &self,
+        _doc_id: u32,
+        _version_id: u32,
+        _cited: Citation
+    ) {}
+
+    default fn _emit_document_forked_event(
+        &self,
+        _doc_id: u32,
+        _parent_doc_id: u32,
+        _version_id: u32
+    ) {}
+
+    default fn _emit_royalty_paid_event(
+        &self,
+        _doc_id: u32,
+        _parent_doc_id: u32,
+        _recipient: AccountId,
+        _amount: Balance
+    ) {}
+
+    default fn _emit_mirror_added_event(
+        &self,
+        _doc_id: u32,
+        _version_id: u32,
+        _locator: Option<ContentLocator>
+    ) {}
+
+    default fn _emit_access_purchased_event(
+        &self,
+        _doc_id: u32,
+        _buyer: AccountId,
+        _price: Balance,
+        _expires_at: Option<Timestamp>
+    ) {}
+
+    default fn _emit_fees_withdrawn_event(&self, _to: AccountId, _amount: Balance) {}
+
+    default fn _document(&self, doc_id: u32) -> Result<NftDocument, Error> {
+        self.data().docs.get(&doc_id).ok_or(Error::DocumentNotFound)
+    }
+
+    default fn _version(&self, doc_id: u32, version_id: u32) -> Result<Version, Error> {
+        self.data().versions.get(&(doc_id, version_id)).ok_or(Error::VersionNotFound)
+    }
+
+    default fn _comment(&self, comment_id: u32) -> Result<Comment, Error> {
+        self.data().comments.get(&comment_id).ok_or(Error::CommentNotFound)
+    }
+
+    default fn _ensure_can_comment(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        account: AccountId
+    ) -> Result<(), Error> {
+        let doc = self._document(doc_id)?;
+        self._version(doc_id, version_id)?;
+
+        if !self._can_view(&doc, version_id, account) {
+            return Err(Error::NotAllowed);
+        }
+
+        Ok(())
+    }
+
+    default fn _ensure_valid_comment(
+        &self,
+        body: &str,
+        ipfs_hash: &Option<String>
+    ) -> Result<(), Error> {
+        if body.len() > (MAX_COMMENT_LENGTH as usize) {
+            return Err(Error::InvalidComment);
+        }
+
+        match ipfs_hash {
+            Some(ipfs_hash) if !is_valid_cid(ipfs_hash) => Err(Error::InvalidContentHash),
+            None if body.is_empty() => Err(Error::InvalidComment),
+            _ => Ok(()),
+        }
+    }
+
+    default fn _document_of_owner(&self, doc_id: u32) -> Result<NftDocument, Error> {
+        let doc = self._document(doc_id)?;
+
+        if doc.owner != Self::env().caller() {
+            return Err(Error::NotOwner);
+        }
+
+        Ok(doc)
+    }
+
+    default fn _document_of_reviewer(&self, doc_id: u32) -> Result<NftDocument, Error> {
+        let doc = self._document(doc_id)?;
+        let caller = Self::env().caller();
+
+        if doc.owner != caller && !doc.reviewers.contains(&caller) {
+            return Err(Error::NotAllowed);
+        }
+
+        Ok(doc)
+    }
+
+    default fn _ensure_can_sign_off(
+        &self,
+        doc: &NftDocument,
+        version: &Version,
+        account: AccountId
+    ) -> Result<(), Error> {
+        if version.contributor == account && (doc.owner != account || !doc.reviewers.is_empty()) {
+            return Err(Error::NotAllowed);
+        }
+
+        Ok(())
+    }
+
+    default fn _ensure_admin(&self) -> Result<(), Error> {
+        if Self::env().caller() != self.data().admin {
+            return Err(Error::NotOwner);
+        }
+
+        Ok(())
+    }
+
+    default fn _add_nft(&mut self, nft: NftDocument) -> Result<(), Error> {
+        if nft.id != self.data().next_doc_id {
+            return Err(Error::CannotInsert);
+        }
+
+        self.data().next_doc_id = nft.id.checked_add(1).ok_or(Error::DocumentLimitReached)?;
+        self.data().docs.insert(&nft.id, &nft);
+        self._index_insert(DocumentIndex::Owner(nft.owner), nft.id);
+
+        self._emit_document_created_event(nft.id, nft.owner, nft.title);
+
+        Ok(())
+    }
+
+    default fn _migrate_document(&mut self, doc_id: u32) -> Result<(), Error> {
+        if self.data().versions.contains(&(doc_id, 0)) {
+            return Ok(());
+        }
+
+        // Legacy entries sit under the same mappings, encoded with the old layout and keys.
+        let docs_key = self.data().docs.key();
+        let versions_key = self.data().versions.key();
+        let reasons_key = self.data().rejection_reasons.key();
+
+        let legacy_doc: LegacyNftDocument = ink::env::get_contract_storage(&(docs_key, doc_id))
+            .map_err(|_| Error::CannotFetchValue)?
+            .ok_or(Error::DocumentNotFound)?;
+        let doc: NftDocument = legacy_doc.into();
+
+        self.data().docs.insert(&doc_id, &doc);
+        self.data().next_doc_id = self.data().next_doc_id.max(doc_id.saturating_add(1));
+        self._index_insert(DocumentIndex::Owner(doc.owner), doc_id);
+        self._index_insert(DocumentIndex::State(DocumentState::Publish), doc_id);
+
+        // `u8` ids wrapped around, so at most 256 distinct legacy entries can exist.
+        for version_id in 0..doc.number_upload.min(256) {
+            let legacy_id = version_id as u8;
+            let legacy_key = (versions_key, (doc_id, legacy_id));
+            let legacy_version: Option<LegacyVersion> = ink::env::get_contract_storage(
+                &legacy_key
+            ).map_err(|_| Error::CannotFetchValue)?;
+
+            if let Some(legacy_version) = legacy_version {
+                let version: Version = legacy_version.into();
+
+                self._set_version_attribute(&doc, &version)?;
+                self.data().versions.insert(&(doc_id, version.version_id), &version);
+                self._index_insert(DocumentIndex::Contributor(version.contributor), doc_id);
+                ink::env::clear_contract_storage(&legacy_key);
+            }
+
+            let legacy_reason_key = (reasons_key, (doc_id, legacy_id));
+            let reason: Option<String> = ink::env::get_contract_storage(
+                &legacy_reason_key
+            ).map_err(|_| Error::CannotFetchValue)?;
+
+            if let Some(reason) = reason {
+                self.data().rejection_reasons.insert(&(doc_id, version_id), &reason);
+                ink::env::clear_contract_storage(&legacy_reason_key);
+            }
+        }
+
+        Ok(())
+    }
+
+    default fn _is_member(&self, doc: &NftDocument, account: AccountId) -> bool {
+        doc.owner == account ||
+            doc.reviewers.contains(&account) ||
+            doc.post_owner.iter().any(|post_owner| post_owner.user == account)
+    }
+
+    default fn _is_retracted(&self, doc: &NftDocument) -> bool {
+        match self.data().versions.get(&(doc.id, doc.version_id_publish)) {
+            Some(version) => version.state == DocumentState::Retracted,
+            None => false,
+        }
+    }
+
+    default fn _is_listed(&self, doc: &NftDocument) -> bool {
+        doc.visibility == Visibility::Public && !self._is_retracted(doc)
+    }
+
+    default fn _can_view(&self, doc: &NftDocument, version_id: u32, account: AccountId) -> bool {
+        if self._is_member(doc, account) {
+            return true;
+        }
+
+        if self._is_retracted(doc) {
+            return false;
+        }
+
+        doc.visibility != Visibility::Private ||
+            self.data().key_envelopes.contains(&(doc.id, version_id, account))
+    }
+
+    default fn _redact_document(&self, mut doc: NftDocument) -> NftDocument {
+        if !self._can_view(&doc, doc.version_id_publish, Self::env().caller()) {
+            doc.locator = None;
+        }
+
+        doc
+    }
+
+    default fn _redact_version(&self, doc: &NftDocument, mut version: Version) -> Version {
+        if !self._can_view(doc, version.version_id, Self::env().caller()) {
+            version.locator = None;
+            version.mirrors = Vec::new();
+        }
+
+        version
+    }
+
+    default fn _merge_allocations(
+        &self,
+        total_supply: Balance,
+        allocations: Vec<(AccountId, Balance)>
+    ) -> Result<Vec<(AccountId, Balance)>, Error> {
+        if allocations.len() > (MAX_ALLOCATIONS as usize) {
+            return Err(Error::InvalidAllocation);
+        }
+
+        let mut merged: Vec<(AccountId, Balance)> = Vec::with_capacity(allocations.len());
+        let mut allocated: Balance = 0;
+
+        for (account, amount) in allocations {
+            if amount == 0 {
+                return Err(Error::InvalidAllocation);
+            }
+
+            allocated = allocated.checked_add(amount).ok_or(Error::InvalidAllocation)?;
+
+            match merged.iter_mut().find(|(recipient, _)| *recipient == account) {
+                Some((_, merged_amount)) => {
+                    *merged_amount += amount;
+                }
+                None => merged.push((account, amount)),
+            }
+        }
+
+        if total_supply == 0 || allocated != total_supply {
+            return Err(Error::InvalidAllocation);
+        }
+
+        Ok(merged)
+    }
+
+    default fn _ensure_can_create_version(
+        &self,
+        doc_id: u32,
+        account: AccountId
+    ) -> Result<(), Error> {
+        let doc = self._document(doc_id)?;
+
+        if self._is_retracted(&doc) {
+            return Err(Error::DocumentRetracted);
+        }
+
+        if doc.owner == account {
+            return Ok(());
+        }
+
+        let allowed = doc.post_owner
+            .iter()
+            .any(|post_owner| post_owner.user == account && post_owner.user_role.can_create_version());
+
+        if !allowed {
+            return Err(Error::NotAllowed);
+        }
+
+        Ok(())
+    }
+
+    default fn _public_locator(
+        &self,
+        doc: &NftDocument,
+        version: &Version
+    ) -> Option<ContentLocator> {
+        if doc.visibility == Visibility::Private || self._is_retracted(doc) {
+            return None;
+        }
+
+        version.locator.clone()
+    }
+
+    default fn _set_version_attribute(
+        &self,
+        doc: &NftDocument,
+        version: &Version
+    ) -> Result<(), Error> {
+        let locators = version.locators();
+        let data = if doc.visibility == Visibility::Private || self._is_retracted(doc) {
+            let mut digest = [0u8; 32];
+            hash_encoded::<Blake2x256, _>(&locators, &mut digest);
+            digest.to_vec()
+        } else {
+            scale::Encode::encode(&locators)
+        };
+
+        NftTokenAttributesRef::set_attribute(
+            &self.data().nft_token,
+            Id::U32(doc.id),
+            version_attribute_key(version.version_id),
+            data
+        )?;
+
+        Ok(())
+    }
+
+    default fn _refresh_version_attributes(&self, doc: &NftDocument) -> Result<(), Error> {
+        for version_id in 0..doc.number_upload {
+            let version = self._version(doc.id, version_id)?;
+            self._set_version_attribute(doc, &version)?;
+        }
+
+        Ok(())
+    }
+
+    default fn _publish_version(
+        &mut self,
+        doc: &mut NftDocument,
+        version: &mut Version
+    ) -> Result<(), Error> {
+        let mut published = self._version(doc.id, doc.version_id_publish)?;
+
+        if published.state == DocumentState::Retracted {
+            return Err(Error::DocumentRetracted);
+        }
+
+        published.state = DocumentState::Archived;
+        self.data().versions.insert(&(doc.id, published.version_id), &published);
+
+        version.state = DocumentState::Publish;
+        self.data().versions.insert(&(doc.id, version.version_id), version);
+
+        doc.locator = version.locator.clone();
+        doc.version_id_publish = version.version_id;
+        self.data().docs.insert(&doc.id, doc);
+
+        self._emit_version_archived_event(doc.id, published.contributor, published.version_id);
+        self._emit_version_published_event(doc.id, version.contributor, version.version_id);
+
+        Ok(())
+    }
+
+    default fn _paginate<F>(&self, length: u32, offset: u32, limit: u32, doc_at: F) -> Vec<NftDocument>
+        where F: Fn(u32) -> Option<u32>
+    {
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(length);
+
+        (offset..end)
+            .filter_map(doc_at)
+            .filter_map(|doc_id| self.data().docs.get(&doc_id))
+            .map(|doc| self._redact_document(doc))
+            .collect()
+    }
+
+    default fn _paginate_index(
+        &self,
+        index: DocumentIndex,
+        offset: u32,
+        limit: u32
+    ) -> Vec<NftDocument> {
+        self._paginate(self._index_length(&index), offset, limit, |position|
+            self._index_entry(&index, position)
+        )
+    }
+
+    default fn _award_points(&mut self, doc: &mut NftDocument, contributor: AccountId) {
+        let award = POINTS_PER_PUBLISHED_VERSION;
+
+        let points = self.data().points.get(&contributor).unwrap_or(0);
+        self.data().points.insert(&contributor, &points.saturating_add(award));
+
+        if
+            let Some(post_owner) = doc.post_owner
+                .iter_mut()
+                .find(|post_owner| post_owner.user == contributor)
+        {
+            post_owner.point = post_owner.point.saturating_add(award);
+        }
+    }
+
+    default fn _unclaimed_points(&self, account: AccountId) -> u32 {
+        let points = self.data().points.get(&account).unwrap_or(0);
+
+        points - self.data().claimed_points.get(&account).unwrap_or(0)
+    }
+
+    default fn _pending_revenue(
+        &self,
+        doc_id: u32,
+        account: AccountId,
+        shares: Balance
+    ) -> Balance {
+        let revenue_per_share = self.data().revenue_per_share.get(&doc_id).unwrap_or(0);
+        // Checkpoints of former holders outlive the cleared total of a deleted document.
+        let paid = self.data().revenue_per_share_paid.get(&(doc_id, account)).unwrap_or(0);
+
+        revenue_earned(shares, revenue_per_share, paid)
+    }
+
+    default fn _settle_revenue(&mut self, doc_id: u32, account: AccountId, shares: Balance) {
+        let pending = self._pending_revenue(doc_id, account, shares);
+        let revenue_per_share = self.data().revenue_per_share.get(&doc_id).unwrap_or(0);
+
+        if pending > 0 {
+            let credited = self.data().revenue_credited.get(&(doc_id, account)).unwrap_or(0);
+            self.data()
+                .revenue_credited.insert(&(doc_id, account), &credited.saturating_add(pending));
+        }
+
+        self.data().revenue_per_share_paid.insert(&(doc_id, account), &revenue_per_share);
+    }
+
+    default fn _royalty(&self, doc_id: u32, amount: Balance) -> Option<(NftDocument, Balance)> {
+        let provenance = self.data().provenance.get(&doc_id)?;
+        // Nobody collects royalties for a deleted parent.
+        let parent = self.data().docs.get(&provenance.parent_doc_id)?;
+
+        let royalty = amount.saturating_mul(provenance.royalty as Balance) /
+            (FEE_DENOMINATOR as Balance);
+
+        if royalty == 0 {
+            return None;
+        }
+
+        Some((parent, royalty))
+    }
+
+    default fn _pay_royalty(&mut self, doc_id: u32, amount: Balance) -> Result<Balance, Error> {
+        let (parent, royalty) = match self._royalty(doc_id, amount) {
+            Some(royalty) => royalty,
+            None => {
+                return Ok(0);
+            }
+        };
+
+        PSP22Ref::transfer(&self.data().native_token, parent.owner, royalty, Vec::new()).map_err(
+            |_| Error::TransferFailed
+        )?;
+
+        self._emit_royalty_paid_event(doc_id, parent.id, parent.owner, royalty);
+
+        Ok(royalty)
+    }
+
+    default fn _set_document_owner(&mut self, doc: &mut NftDocument, new_owner: AccountId) {
+        let previous_owner = doc.owner;
+
+        doc.owner = new_owner;
+        self.data().docs.insert(&doc.id, doc);
+        self._index_remove(DocumentIndex::Owner(previous_owner), doc.id);
+        self._index_insert(DocumentIndex::Owner(new_owner), doc.id);
+
+        self._emit_document_ownership_transferred_event(doc.id, previous_owner, new_owner);
+    }
+
+    default fn _remove_references(&mut self, doc_id: u32, version_id: u32) {
+        let length = self.data().references_length.get(&(doc_id, version_id)).unwrap_or(0);
+
+        for index in 0..length {
+            if let Some(cited) = self.data().references.get(&(doc_id, version_id, index)) {
+                self.data().cites.remove(&(doc_id, version_id, cited.clone()));
+                self._index_remove(DocumentIndex::CitedBy(cited.doc_id), doc_id);
+                self.data().cited_by_version.remove(&(cited.doc_id, doc_id));
+            }
+
+            self.data().references.remove(&(doc_id, version_id, index));
+        }
+
+        self.data().references_length.remove(&(doc_id, version_id));
+    }
+
+    default fn _index_length(&self, index: &DocumentIndex) -> u32 {
+        self.data().index_lengths.get(index).unwrap_or(0)
+    }
+
+    default fn _index_entry(&self, index: &DocumentIndex, position: u32) -> Option<u32> {
+        self.data().index_entries.get(&(index.clone(), position))
+    }
+
+    default fn _index_insert(&mut self, index: DocumentIndex, doc_id: u32) -> bool {
+        if self.data().index_positions.contains(&(index.clone(), doc_id)) {
+            return false;
+        }
+
+        let length = self._index_length(&index);
+
+        self.data().index_entries.insert(&(index.clone(), length), &doc_id);
+        self.data().index_positions.insert(&(index.clone(), doc_id), &length);
+        self.data().index_lengths.insert(&index, &(length + 1));
+
+        true
+    }
+
+    default fn _index_remove(&mut self, index: DocumentIndex, doc_id: u32) {
+        let position = match self.data().index_positions.get(&(index.clone(), doc_id)) {
+            Some(position) => position,
+            None => {
+                return;
+            }
+        };
+        let last = self._index_length(&index) - 1;
+
+        if position != last {
+            if let Some(last_doc_id) = self._index_entry(&index, last) {
+                self.data().index_entries.insert(&(index.clone(), position), &last_doc_id);
+                self.data().index_positions.insert(&(index.clone(), last_doc_id), &position);
+            }
+        }
+
+        self.data().index_entries.remove(&(index.clone(), last));
+        self.data().index_positions.remove(&(index.clone(), doc_id));
+        self.data().index_lengths.insert(&index, &last);
+    }
+}
+
+/// Growth of the revenue per share of a document when `amount` is shared among `total_supply`
+/// shares.
+fn revenue_per_share_increase(amount: Balance, total_supply: Balance) -> Option<Balance> {
+    amount.checked_mul(REVENUE_PRECISION)?.checked_div(total_supply)
+}
+
+/// Revenue earned by `shares` while the revenue per share of their document grew from `paid`.
+fn revenue_earned(shares: Balance, revenue_per_share: Balance, paid: Balance) -> Balance {
+    shares.saturating_mul(revenue_per_share.saturating_sub(paid)) / REVENUE_PRECISION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{ test::{ default_accounts, set_caller, DefaultAccounts }, DefaultEnvironment };
+
+    const CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+    /// Contract embedding the platform, for messages that do not call the token contracts.
+    #[derive(Default, Storage)]
+    struct Platform {
+        #[storage_field]
+        managing_logic: Data,
+    }
+
+    fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+        default_accounts::<DefaultEnvironment>()
+    }
+
+    /// Stores a public document of `owner` with a published version 0.
+    fn add_document(platform: &mut Platform, owner: AccountId) -> u32 {
+        let doc_id = platform.managing_logic.next_doc_id;
+        let doc = NftDocument {
+            id: doc_id,
+            owner,
+            title: String::from("Specification"),
+            locator: Some(ContentLocator::Ipfs(String::from(CID))),
+            post_owner: Vec::new(),
+            reviewers: Vec::new(),
+            version_id_publish: 0,
+            number_upload: 0,
+            visibility: Visibility::Public,
+        };
+
+        platform._add_nft(doc).unwrap();
+        platform._index_insert(DocumentIndex::State(DocumentState::Publish), doc_id);
+        add_version(platform, doc_id, owner, DocumentState::Publish);
+
+        doc_id
+    }
+
+    /// Stores a version the way `create_version_document` does, without minting its token.
+    fn add_version(
+        platform: &mut Platform,
+        doc_id: u32,
+        contributor: AccountId,
+        state: DocumentState
+    ) -> u32 {
+        let mut doc = platform._document(doc_id).unwrap();
+        let version = Version {
+            version_id: doc.number_upload,
+            contributor,
+            locator: Some(ContentLocator::Ipfs(String::from(CID))),
+            state,
+            mirrors: Vec::new(),
+        };
+
+        doc.number_upload += 1;
+        platform.managing_logic.docs.insert(&doc_id, &doc);
+        platform.managing_logic.versions.insert(&(doc_id, version.version_id), &version);
+        platform._index_insert(DocumentIndex::Contributor(contributor), doc_id);
+
+        version.version_id
+    }
+
+    fn doc_ids(docs: Vec<NftDocument>) -> Vec<u32> {
+        docs.iter().map(|doc| doc.id).collect()
+    }
+
+    fn comment_ids(comments: Vec<Comment>) -> Vec<u32> {
+        comments.iter().map(|comment| comment.id).collect()
+    }
+
+    #[test]
+    fn payments_are_shared_pro_rata() {
+        let revenue_per_share = revenue_per_share_increase(1000, 100).unwrap();
+
+        assert_eq!(revenue_earned(60, revenue_per_share, 0), 600);
+        assert_eq!(revenue_earned(40, revenue_per_share, 0), 400);
+    }
+
+    #[test]
+    fn settled_revenue_is_not_earned_twice() {
+        let first = revenue_per_share_increase(1000, 100).unwrap();
+        let second = first + revenue_per_share_increase(500, 100).unwrap();
+
+        assert_eq!(revenue_earned(60, first, first), 0);
+        assert_eq!(revenue_earned(60, second, first), 300);
+    }
+
+    #[test]
+    fn revenue_follows_shares_moved_after_settlement() {
+        // A holds 60 shares and B 40 when the first payment comes in; both settle before A
+        // sends 20 shares to B, then a second payment comes in.
+        let first = revenue_per_share_increase(1000, 100).unwrap();
+        let a_credited = revenue_earned(60, first, 0);
+        let b_credited = revenue_earned(40, first, 0);
+        let second = first + revenue_per_share_increase(1000, 100).unwrap();
+
+        assert_eq!(a_credited + revenue_earned(40, second, first), 1000);
+        assert_eq!(b_credited + revenue_earned(60, second, first), 1000);
+    }
+
+    #[test]
+    fn small_payments_are_not_rounded_away() {
+        let revenue_per_share = revenue_per_share_increase(1, DEFAULT_SHARE_SUPPLY).unwrap();
+
+        assert!(revenue_per_share > 0);
+        assert_eq!(revenue_earned(DEFAULT_SHARE_SUPPLY, revenue_per_share, 0), 1);
+    }
+
+    #[test]
+    fn payments_too_small_to_share_are_detected() {
+        assert_eq!(revenue_per_share_increase(1, REVENUE_PRECISION + 1), Some(0));
+    }
+
+    #[test]
+    fn overflowing_payments_are_rejected() {
+        assert_eq!(revenue_per_share_increase(Balance::MAX, 1), None);
+    }
+
+    #[test]
+    fn holders_who_joined_later_earn_nothing_from_earlier_payments() {
+        let revenue_per_share = revenue_per_share_increase(1000, 100).unwrap();
+
+        assert_eq!(revenue_earned(0, revenue_per_share, 0), 0);
+        assert_eq!(revenue_earned(10, revenue_per_share, revenue_per_share + 1), 0);
+    }
+
+    #[ink::test]
+    fn removing_from_an_index_moves_its_last_entry_into_the_gap() {
+        let mut platform = Platform::default();
+        let index = DocumentIndex::Tag(String::from("rust"));
+
+        for doc_id in 0..4 {
+            assert!(platform._index_insert(index.clone(), doc_id));
+        }
+        assert!(!platform._index_insert(index.clone(), 2));
+
+        platform._index_remove(index.clone(), 1);
+        platform._index_remove(index.clone(), 3);
+        platform._index_remove(index.clone(), 7);
+
+        let entries: Vec<Option<u32>> = (0..3)
+            .map(|position| platform._index_entry(&index, position))
+            .collect();
+
+        assert_eq!(platform._index_length(&index), 2);
+        assert_eq!(entries, vec![Some(0), Some(2), None]);
+
+        platform._index_remove(index.clone(), 0);
+
+        assert_eq!(platform._index_entry(&index, 0), Some(2));
+        assert_eq!(platform.managing_logic.index_positions.get(&(index.clone(), 2)), Some(0));
+        assert_eq!(platform.managing_logic.index_positions.get(&(index, 0)), None);
+    }
+
+    #[ink::test]
+    fn owner_listings_follow_ownership_changes() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+
+        for _ in 0..3 {
+            add_document(&mut platform, accounts.alice);
+        }
+
+        let mut doc = platform._document(0).unwrap();
+        platform._set_document_owner(&mut doc, accounts.bob);
+
+        let alice_docs = platform.get_documents_by_owner(accounts.alice, 0, 10).unwrap();
+        let bob_docs = platform.get_documents_by_owner(accounts.bob, 0, 10).unwrap();
+
+        assert_eq!(doc_ids(alice_docs), vec![2, 1]);
+        assert_eq!(doc_ids(bob_docs), vec![0]);
+        assert_eq!(platform.get_documents_by_owner_length(accounts.alice), Ok(2));
+    }
+
+    #[ink::test]
+    fn listing_pages_skip_unlisted_documents() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+
+        for _ in 0..3 {
+            add_document(&mut platform, accounts.alice);
+        }
+
+        let mut doc = platform._document(1).unwrap();
+        doc.visibility = Visibility::Unlisted;
+        platform.managing_logic.docs.insert(&doc.id, &doc);
+
+        assert_eq!(doc_ids(platform.get_nfts_page(0, 2).unwrap()), vec![0]);
+        assert_eq!(doc_ids(platform.get_nfts_page(2, 2).unwrap()), vec![2]);
+        assert_eq!(platform.get_nfts_length(), Ok(3));
+    }
+
+    #[ink::test]
+    fn owners_need_a_reviewer_to_approve_their_versions() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let version_id = add_version(
+            &mut platform,
+            doc_id,
+            accounts.alice,
+            DocumentState::PendingReview
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_reviewer(doc_id, accounts.bob).unwrap();
+
+        assert_eq!(platform.approve_version(doc_id, version_id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+
+        assert_eq!(platform.approve_version(doc_id, version_id), Ok(()));
+        assert_eq!(platform._document(doc_id).unwrap().version_id_publish, version_id);
+        assert_eq!(platform._version(doc_id, 0).unwrap().state, DocumentState::Archived);
+        assert_eq!(platform._version(doc_id, version_id).unwrap().state, DocumentState::Publish);
+    }
+
+    #[ink::test]
+    fn owners_without_reviewers_approve_their_own_versions() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let version_id = add_version(
+            &mut platform,
+            doc_id,
+            accounts.alice,
+            DocumentState::PendingReview
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+
+        assert_eq!(platform.approve_version(doc_id, version_id), Ok(()));
+        assert_eq!(platform._document(doc_id).unwrap().version_id_publish, version_id);
+    }
+
+    #[ink::test]
+    fn reviewers_cannot_approve_their_own_versions() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let version_id = add_version(
+            &mut platform,
+            doc_id,
+            accounts.bob,
+            DocumentState::PendingReview
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_reviewer(doc_id, accounts.bob).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(platform.approve_version(doc_id, version_id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        assert_eq!(platform.approve_version(doc_id, version_id), Ok(()));
+    }
+
+    #[ink::test]
+    fn versions_are_submitted_by_their_contributor_and_reviewed_once() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let version_id = add_version(&mut platform, doc_id, accounts.charlie, DocumentState::Draft);
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_reviewer(doc_id, accounts.bob).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(platform.submit_version_for_review(doc_id, version_id), Err(Error::NotAllowed));
+        assert_eq!(platform.approve_version(doc_id, version_id), Err(Error::InvalidVersionState));
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        assert_eq!(platform.submit_version_for_review(doc_id, version_id), Ok(()));
+        assert_eq!(
+            platform.submit_version_for_review(doc_id, version_id),
+            Err(Error::InvalidVersionState)
+        );
+        assert_eq!(platform.approve_version(doc_id, version_id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        let reason = String::from("Missing sources");
+
+        assert_eq!(platform.reject_version(doc_id, version_id, reason.clone()), Ok(()));
+        assert_eq!(platform.get_rejection_reason(doc_id, version_id), Ok(Some(reason)));
+        assert_eq!(platform.approve_version(doc_id, version_id), Err(Error::InvalidVersionState));
+        assert_eq!(platform._document(doc_id).unwrap().version_id_publish, 0);
+    }
+
+    #[ink::test]
+    fn owners_cannot_republish_their_versions_past_reviewers() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let version_id = add_version(
+            &mut platform,
+            doc_id,
+            accounts.alice,
+            DocumentState::Archived
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_reviewer(doc_id, accounts.bob).unwrap();
+
+        assert_eq!(platform.publish_version(doc_id, version_id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        assert_eq!(platform.publish_version(doc_id, version_id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(platform.publish_version(doc_id, 0), Err(Error::InvalidVersionState));
+    }
+
+    #[ink::test]
+    fn only_platform_reviewers_earn_contributors_points() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        platform.managing_logic.admin = accounts.alice;
+
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let first = add_version(
+            &mut platform,
+            doc_id,
+            accounts.django,
+            DocumentState::PendingReview
+        );
+        let second = add_version(
+            &mut platform,
+            doc_id,
+            accounts.django,
+            DocumentState::PendingReview
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_reviewer(doc_id, accounts.bob).unwrap();
+        platform.add_reviewer(doc_id, accounts.charlie).unwrap();
+        platform.add_platform_reviewer(accounts.bob).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(platform.add_platform_reviewer(accounts.charlie), Err(Error::NotOwner));
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        platform.approve_version(doc_id, first).unwrap();
+        assert_eq!(platform.get_points(accounts.django), Ok(0));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        platform.approve_version(doc_id, second).unwrap();
+        assert_eq!(platform.get_points(accounts.django), Ok(POINTS_PER_PUBLISHED_VERSION));
+    }
+
+    #[ink::test]
+    fn platform_reviewers_earn_no_points_on_their_own_documents() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        platform.managing_logic.admin = accounts.alice;
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        platform.add_platform_reviewer(accounts.bob).unwrap();
+
+        let doc_id = add_document(&mut platform, accounts.bob);
+        let version_id = add_version(
+            &mut platform,
+            doc_id,
+            accounts.django,
+            DocumentState::PendingReview
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        platform.approve_version(doc_id, version_id).unwrap();
+        assert_eq!(platform.get_points(accounts.django), Ok(0));
+    }
+
+    #[ink::test]
+    fn documents_count_once_towards_the_citations_of_another() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let cited = add_document(&mut platform, accounts.alice);
+        let citing = add_document(&mut platform, accounts.bob);
+        let archived = add_version(&mut platform, citing, accounts.bob, DocumentState::Archived);
+        let draft = add_version(&mut platform, citing, accounts.bob, DocumentState::Draft);
+        let citation = Citation {
+            doc_id: cited,
+            version_id: None,
+        };
+        let self_citation = Citation {
+            doc_id: citing,
+            version_id: None,
+        };
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+
+        assert_eq!(platform.add_citation(citing, 0, citation.clone()), Ok(()));
+        assert_eq!(platform.add_citation(citing, 0, citation.clone()), Err(Error::CitationExists));
+        assert_eq!(platform.add_citation(citing, archived, citation.clone()), Ok(()));
+        assert_eq!(
+            platform.add_citation(citing, draft, citation.clone()),
+            Err(Error::InvalidVersionState)
+        );
+        assert_eq!(platform.add_citation(citing, 0, self_citation), Err(Error::NotAllowed));
+
+        assert_eq!(platform.get_citation_count(cited), Ok(1));
+        assert_eq!(
+            platform.get_cited_by(cited, 0, 10),
+            Ok(
+                vec![Citation {
+                    doc_id: citing,
+                    version_id: Some(0),
+                }]
+            )
+        );
+        assert_eq!(
+            platform.get_references(citing, archived),
+            Ok(vec![(citation, Some(DocumentState::Publish))])
+        );
+    }
+
+    #[ink::test]
+    fn removed_references_leave_the_documents_they_cited() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let cited = add_document(&mut platform, accounts.alice);
+        let first = add_document(&mut platform, accounts.bob);
+        let second = add_document(&mut platform, accounts.charlie);
+        let citation = Citation {
+            doc_id: cited,
+            version_id: Some(0),
+        };
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        platform.add_citation(first, 0, citation.clone()).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        platform.add_citation(second, 0, citation.clone()).unwrap();
+
+        platform._remove_references(first, 0);
+
+        assert_eq!(platform.get_citation_count(cited), Ok(1));
+        assert_eq!(
+            platform.get_cited_by(cited, 0, 10),
+            Ok(
+                vec![Citation {
+                    doc_id: second,
+                    version_id: Some(0),
+                }]
+            )
+        );
+        assert_eq!(platform.get_references(first, 0), Ok(Vec::new()));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+
+        assert_eq!(platform.add_citation(first, 0, citation), Ok(()));
+        assert_eq!(platform.get_citation_count(cited), Ok(2));
+    }
+
+    #[ink::test]
+    fn replies_are_threaded_under_their_parent() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+        let other_version = add_version(
+            &mut platform,
+            doc_id,
+            accounts.alice,
+            DocumentState::Draft
+        );
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        let first = platform.post_comment(doc_id, 0, String::from("First"), None, None).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        let reply = platform
+            .post_comment(doc_id, 0, String::from("Reply"), None, Some(first.id))
+            .unwrap();
+        let second = platform.post_comment(doc_id, 0, String::from("Second"), None, None).unwrap();
+        let misplaced = platform.post_comment(
+            doc_id,
+            other_version,
+            String::from("Elsewhere"),
+            None,
+            Some(first.id)
+        );
+
+        assert_eq!(misplaced.err(), Some(Error::CommentNotFound));
+        assert_eq!(
+            comment_ids(platform.get_comments(doc_id, 0, 0, 10).unwrap()),
+            vec![first.id, second.id]
+        );
+        assert_eq!(comment_ids(platform.get_replies(first.id, 0, 10).unwrap()), vec![reply.id]);
+        assert_eq!(platform.get_comments_length(doc_id, 0), Ok(2));
+        assert_eq!(platform.get_replies_length(reply.id), Ok(0));
+        assert_eq!(platform.get_comment(reply.id).unwrap().parent_id, Some(first.id));
+    }
+
+    #[ink::test]
+    fn comments_are_paged_in_posting_order() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+
+        for body in ["One", "Two", "Three"] {
+            platform.post_comment(doc_id, 0, String::from(body), None, None).unwrap();
+        }
+
+        assert_eq!(comment_ids(platform.get_comments(doc_id, 0, 0, 2).unwrap()), vec![0, 1]);
+        assert_eq!(comment_ids(platform.get_comments(doc_id, 0, 1, 1).unwrap()), vec![1]);
+        assert_eq!(comment_ids(platform.get_comments(doc_id, 0, 2, 10).unwrap()), vec![2]);
+        assert!(platform.get_comments(doc_id, 0, 3, 10).unwrap().is_empty());
+    }
+
+    #[ink::test]
+    fn deleted_comments_keep_their_place_in_threads() {
+        let accounts = accounts();
+        let mut platform = Platform::default();
+        let doc_id = add_document(&mut platform, accounts.alice);
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        let first = platform.post_comment(doc_id, 0, String::from("First"), None, None).unwrap();
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        let reply = platform
+            .post_comment(doc_id, 0, String::from("Reply"), None, Some(first.id))
+            .unwrap();
+
+        assert_eq!(platform.delete_comment(first.id), Err(Error::NotAllowed));
+
+        set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(platform.delete_comment(first.id), Ok(()));
+        assert_eq!(
+            platform.edit_comment(first.id, String::from("Edited"), None),
+            Err(Error::CommentNotFound)
+        );
+
+        let comments = platform.get_comments(doc_id, 0, 0, 10).unwrap();
+
+        assert_eq!(comment_ids(comments.clone()), vec![first.id]);
+        assert!(comments[0].deleted && comments[0].body.is_empty());
+        assert_eq!(comment_ids(platform.get_replies(first.id, 0, 10).unwrap()), vec![reply.id]);
+
+        set_caller::<DefaultEnvironment>(accounts.charlie);
+        let orphan = platform.post_comment(doc_id, 0, String::from("Late"), None, Some(first.id));
+
+        assert_eq!(orphan.err(), Some(Error::CommentNotFound));
+
+        set_caller::<DefaultEnvironment>(accounts.alice);
+        assert_eq!(platform.delete_comment(reply.id), Ok(()));
+    }
+}
@@ -1 +1,4 @@
-pub mod data_structure;
\ No newline at end of file
+pub mod cid;
+pub mod data_structure;
+pub mod managing_logic;
+pub mod spdx;
@@ -0,0 +1,157 @@
+use ink::prelude::vec::Vec;
+
+/// Whether `expression` is a well formed SPDX license expression.
+///
+/// Accepts license identifiers (optionally followed by `+`), `LicenseRef-` and
+/// `DocumentRef-…:LicenseRef-…` references, `WITH` exceptions and `AND` / `OR` combinations
+/// with parentheses. Identifiers are checked for syntax only, not against the SPDX list.
+pub fn is_valid_spdx_expression(expression: &str) -> bool {
+    let tokens = tokenize(expression);
+    let mut rest = tokens.as_slice();
+
+    parse_expression(&mut rest) && rest.is_empty()
+}
+
+/// Splits an expression on whitespace, with each parenthesis as a token of its own.
+fn tokenize(expression: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+
+    for word in expression.split_whitespace() {
+        let mut start = 0;
+
+        for (index, c) in word.char_indices() {
+            if c == '(' || c == ')' {
+                if start < index {
+                    tokens.push(&word[start..index]);
+                }
+
+                tokens.push(&word[index..index + 1]);
+                start = index + 1;
+            }
+        }
+
+        if start < word.len() {
+            tokens.push(&word[start..]);
+        }
+    }
+
+    tokens
+}
+
+fn parse_expression(tokens: &mut &[&str]) -> bool {
+    if !parse_term(tokens) {
+        return false;
+    }
+
+    while let Some((&operator, rest)) = tokens.split_first() {
+        if operator != "AND" && operator != "OR" {
+            break;
+        }
+
+        *tokens = rest;
+
+        if !parse_term(tokens) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn parse_term(tokens: &mut &[&str]) -> bool {
+    match tokens.split_first() {
+        Some((&"(", rest)) => {
+            *tokens = rest;
+
+            if !parse_expression(tokens) {
+                return false;
+            }
+
+            match tokens.split_first() {
+                Some((&")", rest)) => {
+                    *tokens = rest;
+                    true
+                }
+                _ => false,
+            }
+        }
+        Some((&license, rest)) if is_license(license) => {
+            *tokens = rest;
+
+            if let Some((&"WITH", rest)) = tokens.split_first() {
+                match rest.split_first() {
+                    Some((&exception, rest)) if is_idstring(exception) => {
+                        *tokens = rest;
+                    }
+                    _ => {
+                        return false;
+                    }
+                }
+            }
+
+            true
+        }
+        _ => false,
+    }
+}
+
+fn is_license(token: &str) -> bool {
+    let token = token.strip_suffix('+').unwrap_or(token);
+
+    match token.split_once(':') {
+        Some((document, license)) =>
+            document.starts_with("DocumentRef-") &&
+                license.starts_with("LicenseRef-") &&
+                is_idstring(document) &&
+                is_idstring(license),
+        None => is_idstring(token),
+    }
+}
+
+/// Letters, digits, `-` and `.`, as SPDX allows in identifiers; operators excluded.
+fn is_idstring(token: &str) -> bool {
+    !token.is_empty() &&
+        !matches!(token, "AND" | "OR" | "WITH") &&
+        token.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_license_identifiers_and_references() {
+        assert!(is_valid_spdx_expression("MIT"));
+        assert!(is_valid_spdx_expression("CC-BY-4.0"));
+        assert!(is_valid_spdx_expression("GPL-2.0+"));
+        assert!(is_valid_spdx_expression("LicenseRef-my-license"));
+        assert!(is_valid_spdx_expression("DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2"));
+    }
+
+    #[test]
+    fn accepts_compound_expressions() {
+        assert!(is_valid_spdx_expression("MIT OR Apache-2.0"));
+        assert!(is_valid_spdx_expression("GPL-2.0-or-later WITH Classpath-exception-2.0"));
+        assert!(is_valid_spdx_expression("(MIT OR Apache-2.0) AND BSD-3-Clause"));
+        assert!(is_valid_spdx_expression("((MIT))"));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(!is_valid_spdx_expression(""));
+        assert!(!is_valid_spdx_expression("MIT OR"));
+        assert!(!is_valid_spdx_expression("MIT AND AND Apache-2.0"));
+        assert!(!is_valid_spdx_expression("(MIT"));
+        assert!(!is_valid_spdx_expression("MIT)"));
+        assert!(!is_valid_spdx_expression("MIT WITH"));
+        assert!(!is_valid_spdx_expression("(MIT) WITH Classpath-exception-2.0"));
+        assert!(!is_valid_spdx_expression("MIT Apache-2.0"));
+    }
+
+    #[test]
+    fn rejects_characters_outside_identifiers() {
+        assert!(!is_valid_spdx_expression("MIT/Apache-2.0"));
+        assert!(!is_valid_spdx_expression("Creative Commons"));
+        assert!(!is_valid_spdx_expression("LicenseRef-a:LicenseRef-b"));
+    }
+}
@@ -1,9 +1,9 @@
-use openbrush::traits::{ String, Balance };
-use ink::prelude::vec::Vec;
+use openbrush::traits::{ AccountId, String, Balance };
+use ink::prelude::{ format, vec::Vec };
 use crate::impls::managing_logic::data_structure::*;
-use openbrush::contracts::traits::psp37::{ extensions::{ metadata::* } };
+use openbrush::contracts::traits::psp37::{ PSP37Error, extensions::{ metadata::* } };
 
-#[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum Error {
     NotOwner,
@@ -13,52 +13,596 @@ pub enum Error {
     CannotInsert,
     CannotFetchValue,
     NotAllowed,
+    DocumentNotFound,
+    VersionNotFound,
+    CollaboratorExists,
+    CollaboratorNotFound,
+    NothingToClaim,
+    InsufficientRewardPool,
+    TransferFailed,
+    InvalidVersionState,
+    ReviewerExists,
+    ReviewerNotFound,
+    DocumentLimitReached,
+    VersionLimitReached,
+    UpgradeFailed,
+    InvalidTokenContract,
+    InvalidAllocation,
+    InvalidAmount,
+    NotForSale,
+    KeyEnvelopeNotFound,
+    InvalidContentHash,
+    MirrorExists,
+    MirrorLimitReached,
+    InvalidTag,
+    TagLimitReached,
+    InvalidMetadata,
+    DocumentRetracted,
+    CitationExists,
+    ReferenceLimitReached,
+    CommentNotFound,
+    InvalidComment,
+    PSP37Error(PSP37Error),
 }
 
-#[derive(scale::Encode, scale::Decode, Clone, Debug)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+impl From<PSP37Error> for Error {
+    fn from(error: PSP37Error) -> Self {
+        Error::PSP37Error(error)
+    }
+}
+
+impl From<Error> for PSP37Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::PSP37Error(error) => error,
+            error => PSP37Error::Custom(format!("{:?}", error)),
+        }
+    }
+}
+
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub enum UserRole {
     Developer,
     CoAuthor,
     Partner,
 }
 
-#[derive(scale::Encode, scale::Decode, Clone, Debug)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+impl UserRole {
+    /// Whether a collaborator with this role may upload new versions of a document.
+    pub fn can_create_version(&self) -> bool {
+        matches!(self, UserRole::Developer | UserRole::CoAuthor)
+    }
+}
+
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub enum DocumentState {
     Publish,
     Archived,
+    Draft,
+    PendingReview,
+    Rejected,
+    Retracted,
 }
 
+/// Who can find a document and read its content hashes.
+///
+/// Unlisted documents are left out of the platform-wide listings, and the hashes of private
+/// documents are only returned to members and to accounts holding a key envelope.
+#[derive(scale::Encode, scale::Decode, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Visibility {
+    Public,
+    Unlisted,
+    Private,
+}
+
+/// Upper bound on the number of documents returned by a single paginated query.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Number of shares minted for a document when its creator does not choose a supply.
+pub const DEFAULT_SHARE_SUPPLY: Balance = 100000;
+
+/// Upper bound on the number of entries in the initial split of a document's shares.
+pub const MAX_ALLOCATIONS: u32 = 16;
+
+/// Scale applied to the revenue per share of a document so small payments are not rounded away.
+pub const REVENUE_PRECISION: Balance = 1_000_000_000_000;
+
+/// Upper bound on the number of mirrors a version can list besides its primary locator.
+pub const MAX_MIRRORS: u32 = 8;
+
+/// Upper bound on the length of a URL locator, in bytes.
+pub const MAX_URL_LENGTH: u32 = 256;
+
+/// Upper bound on the number of tags of a document.
+pub const MAX_TAGS: u32 = 10;
+
+/// Upper bound on the length of a tag, in bytes.
+pub const MAX_TAG_LENGTH: u32 = 32;
+
+/// Upper bound on the length of a document description, in bytes.
+pub const MAX_DESCRIPTION_LENGTH: u32 = 1024;
+
+/// Upper bound on the length of the license, language and MIME type of a document, in bytes.
+pub const MAX_METADATA_FIELD_LENGTH: u32 = 64;
+
+/// Upper bound on the number of references a version can declare.
+pub const MAX_REFERENCES: u32 = 50;
+
+/// Upper bound on the length of a comment body, in bytes.
+pub const MAX_COMMENT_LENGTH: u32 = 1024;
+
+/// Denominator of the platform fee, which is expressed in basis points.
+pub const FEE_DENOMINATOR: u32 = 10_000;
+
+/// Points credited to a contributor each time a platform reviewer approves one of their versions.
+pub const POINTS_PER_PUBLISHED_VERSION: u32 = 10;
+
 #[openbrush::wrapper]
 pub type ManagingLogicRef = dyn ManagingLogic;
 
+/// Document management on top of the native_token and nft_token contracts.
+///
+/// Contracts embedding it can replace their code and keep their storage; documents stored
+/// before version ids were widened to `u32` are then rewritten with `migrate_documents`.
 #[openbrush::trait_definition]
 pub trait ManagingLogic {
+    /// Returns the number of document ids handed out so far, deleted documents included.
+    #[ink(message)]
+    fn get_nfts_length(&self) -> Result<u32, Error>;
+
+    /// Lists the public documents among ids `offset..offset + limit`.
+    ///
+    /// Deleted and unlisted documents are skipped after paging, so a page may come back short
+    /// or empty before the end: keep paging until `offset` reaches `get_nfts_length`.
+    #[ink(message)]
+    fn get_nfts_page(&self, offset: u32, limit: u32) -> Result<Vec<NftDocument>, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_owner(
+        &self,
+        owner: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_owner_length(&self, owner: AccountId) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_contributor(
+        &self,
+        contributor: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_contributor_length(&self, contributor: AccountId) -> Result<u32, Error>;
+
+    /// Lists documents in `state`; like `get_nfts_page`, pages skip documents that are not
+    /// public and may come back short or empty before the end of the index.
+    #[ink(message)]
+    fn get_documents_by_state(
+        &self,
+        state: DocumentState,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_state_length(&self, state: DocumentState) -> Result<u32, Error>;
+
+    /// Lists public documents carrying `tag`; tags are matched in lowercase.
+    ///
+    /// Like `get_nfts_page`, pages skip documents that are not public and may come back short
+    /// or empty before the end of the index.
+    #[ink(message)]
+    fn get_documents_by_tag(
+        &self,
+        tag: String,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<NftDocument>, Error>;
+
+    #[ink(message)]
+    fn get_documents_by_tag_length(&self, tag: String) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn get_version_doc_length(&self, doc_id: u32) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn get_version(&self, doc_id: u32, version_id: u32) -> Result<Version, Error>;
+
+    #[ink(message)]
+    fn get_versions(&self, doc_id: u32) -> Result<Vec<Version>, Error>;
+
+    /// Returns the PSP37 id of the token minted for a version.
+    #[ink(message)]
+    fn get_version_token_id(&self, doc_id: u32, version_id: u32) -> Result<Id, Error>;
+
+    /// Returns the `(doc_id, version_id)` pair a version token was minted for.
+    #[ink(message)]
+    fn decode_version_token_id(&self, token_id: Id) -> Result<(u32, u32), Error>;
+
+    /// Returns the version tokens among positions `offset..offset + limit` of the tokens
+    /// `account` holds on the nft_token contract; document shares in that range are skipped.
+    #[ink(message)]
+    fn get_version_tokens(
+        &self,
+        account: AccountId,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Id>, Error>;
+
+    #[ink(message)]
+    fn get_document(&self, doc_id: u32) -> Result<NftDocument, Error>;
+
+    /// Creates a document and mints its shares.
+    ///
+    /// `total_supply` defaults to `DEFAULT_SHARE_SUPPLY`. When `allocations` is empty the
+    /// caller receives every share, otherwise its at most `MAX_ALLOCATIONS` entries must add up
+    /// to the supply. Amounts allocated to the same account are added up, and the caller, who
+    /// becomes the owner, must end up with more than half of the supply.
+    ///
+    /// Private documents must be created private: their locators are never emitted.
+    #[ink(message)]
+    fn create_document(
+        &mut self,
+        title: String,
+        locator: ContentLocator,
+        total_supply: Option<Balance>,
+        allocations: Vec<(AccountId, Balance)>,
+        visibility: Visibility
+    ) -> Result<NftDocument, Error>;
+
+    /// Creates a document derived from a version of `parent_doc_id`.
+    ///
+    /// The fork records its origin along with the royalty the parent asks from forks at that
+    /// time, and takes the visibility of the parent. The caller must be able to read the
+    /// source version.
+    #[ink(message)]
+    fn fork_document(
+        &mut self,
+        parent_doc_id: u32,
+        version_id: u32,
+        title: String,
+        locator: ContentLocator
+    ) -> Result<NftDocument, Error>;
+
+    /// Returns the origin of a document, then the origin of its parent and so on.
+    #[ink(message)]
+    fn get_ancestry(&self, doc_id: u32) -> Result<Vec<Provenance>, Error>;
+
+    /// Sets the share of revenue, in basis points, that forks created from now on pay back.
+    #[ink(message)]
+    fn set_fork_royalty(&mut self, doc_id: u32, royalty: u32) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_fork_royalty(&self, doc_id: u32) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn create_version_document(
+        &mut self,
+        doc_id: u32,
+        locator: ContentLocator
+    ) -> Result<Version, Error>;
+
+    /// Withdraws the published version of a document. Its locators are hidden from everyone
+    /// but members, version attributes keep only their digest, and the document no longer
+    /// accepts new versions or mirrors.
+    #[ink(message)]
+    fn retract_document(&mut self, doc_id: u32) -> Result<(), Error>;
+
+    /// Burns the shares of a document, along with the version tokens its contributors still
+    /// hold, and removes it from storage.
+    ///
+    /// The owner must hold every share. Revenue earned so far stays claimable, and the id of
+    /// the document is never given to another one. Its references stop counting towards the
+    /// documents they cite. What stays in storage, out of reach of every message that needs
+    /// the document:
+    /// - its provenance, so forks can still trace their ancestry;
+    /// - the citations and comments it received, which can grow without bound;
+    /// - per-account records: licenses, key envelopes and revenue checkpoints.
+    #[ink(message)]
+    fn delete_document(&mut self, doc_id: u32) -> Result<(), Error>;
+
+    /// Replaces the metadata of a document. Tags are stored in lowercase and must be unique,
+    /// and the license must be an SPDX expression.
+    #[ink(message)]
+    fn set_metadata(&mut self, doc_id: u32, metadata: DocumentMetadata) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_metadata(&self, doc_id: u32) -> Result<DocumentMetadata, Error>;
+
+    /// Changes who can see the content hashes of a document.
+    ///
+    /// Hashes emitted or written to the version attributes while the document was not private
+    /// stay public, so private content should get a new version after the switch.
+    #[ink(message)]
+    fn set_visibility(&mut self, doc_id: u32, visibility: Visibility) -> Result<(), Error>;
+
+    /// Stores the content key of a version, encrypted off-chain for `recipient`.
+    #[ink(message)]
+    fn grant_key_envelope(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId,
+        envelope: Vec<u8>
+    ) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn revoke_key_envelope(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId
+    ) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_key_envelope(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        recipient: AccountId
+    ) -> Result<Option<Vec<u8>>, Error>;
+
+    #[ink(message)]
+    fn submit_version_for_review(&mut self, doc_id: u32, version_id: u32) -> Result<(), Error>;
+
+    /// Publishes a version pending review.
+    ///
+    /// Nobody can approve their own versions, except the owner of a document without reviewers,
+    /// who would otherwise be blocked. The contributor earns points only when the approval
+    /// comes from a platform reviewer other than the owner.
+    #[ink(message)]
+    fn approve_version(&mut self, doc_id: u32, version_id: u32) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn reject_version(&mut self, doc_id: u32, version_id: u32, reason: String) -> Result<(), Error>;
+
+    /// Publishes an archived version again; signed off by the owner or a reviewer under the same
+    /// rules as `approve_version`.
+    #[ink(message)]
+    fn publish_version(&mut self, doc_id: u32, version_id: u32) -> Result<(), Error>;
+
+    /// Lists another location of the content of a version, without creating a new version.
+    #[ink(message)]
+    fn add_mirror(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        locator: ContentLocator
+    ) -> Result<(), Error>;
+
+    /// Returns the primary locator of a version followed by its mirrors.
+    #[ink(message)]
+    fn get_locators(&self, doc_id: u32, version_id: u32) -> Result<Vec<ContentLocator>, Error>;
+
+    /// Declares that a version of `doc_id` cites `cited`. The version must be published or
+    /// archived.
+    #[ink(message)]
+    fn add_citation(&mut self, doc_id: u32, version_id: u32, cited: Citation) -> Result<(), Error>;
+
+    /// Returns the references of a version, each with the current state of the cited version,
+    /// or of the published version when no version is cited. The state is `None` once the
+    /// cited document has been deleted.
+    #[ink(message)]
+    fn get_references(
+        &self,
+        doc_id: u32,
+        version_id: u32
+    ) -> Result<Vec<(Citation, Option<DocumentState>)>, Error>;
+
+    /// Returns the documents citing `doc_id`, each once, as a `Citation` of the first of their
+    /// versions to cite it.
+    #[ink(message)]
+    fn get_cited_by(&self, doc_id: u32, offset: u32, limit: u32) -> Result<Vec<Citation>, Error>;
+
+    /// Returns the number of distinct documents citing `doc_id`.
+    #[ink(message)]
+    fn get_citation_count(&self, doc_id: u32) -> Result<u32, Error>;
+
+    /// Comments on a version, or replies to `parent_id` on the same version.
+    ///
+    /// A comment needs a body, a CID for a longer body stored on IPFS, or both.
+    #[ink(message)]
+    fn post_comment(
+        &mut self,
+        doc_id: u32,
+        version_id: u32,
+        body: String,
+        ipfs_hash: Option<String>,
+        parent_id: Option<u32>
+    ) -> Result<Comment, Error>;
+
+    #[ink(message)]
+    fn edit_comment(
+        &mut self,
+        comment_id: u32,
+        body: String,
+        ipfs_hash: Option<String>
+    ) -> Result<(), Error>;
+
+    /// Deletes a comment. Allowed to its author and, for moderation, to the document owner.
+    #[ink(message)]
+    fn delete_comment(&mut self, comment_id: u32) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_comment(&self, comment_id: u32) -> Result<Comment, Error>;
+
+    /// Lists the top-level comments on a version, oldest first.
+    #[ink(message)]
+    fn get_comments(
+        &self,
+        doc_id: u32,
+        version_id: u32,
+        offset: u32,
+        limit: u32
+    ) -> Result<Vec<Comment>, Error>;
+
+    #[ink(message)]
+    fn get_comments_length(&self, doc_id: u32, version_id: u32) -> Result<u32, Error>;
+
+    /// Lists the replies to a comment, oldest first.
+    #[ink(message)]
+    fn get_replies(&self, comment_id: u32, offset: u32, limit: u32) -> Result<Vec<Comment>, Error>;
+
+    #[ink(message)]
+    fn get_replies_length(&self, comment_id: u32) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn get_rejection_reason(&self, doc_id: u32, version_id: u32) -> Result<Option<String>, Error>;
+
+    #[ink(message)]
+    fn add_reviewer(&mut self, doc_id: u32, reviewer: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn remove_reviewer(&mut self, doc_id: u32, reviewer: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn add_collaborator(
+        &mut self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    ) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn remove_collaborator(&mut self, doc_id: u32, collaborator: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn set_collaborator_role(
+        &mut self,
+        doc_id: u32,
+        collaborator: AccountId,
+        user_role: UserRole
+    ) -> Result<(), Error>;
+
+    /// Moves the caller's shares of the document to `new_owner` and hands over ownership.
+    ///
+    /// The caller must have approved this contract on the nft_token contract beforehand.
+    #[ink(message)]
+    fn transfer_document(&mut self, doc_id: u32, new_owner: AccountId) -> Result<(), Error>;
+
+    /// Makes the caller the owner of a document once they hold a majority of its shares.
+    #[ink(message)]
+    fn claim_document_ownership(&mut self, doc_id: u32) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_points(&self, account: AccountId) -> Result<u32, Error>;
+
+    #[ink(message)]
+    fn get_claimable_rewards(&self, account: AccountId) -> Result<Balance, Error>;
+
+    #[ink(message)]
+    fn claim_rewards(&mut self) -> Result<Balance, Error>;
+
+    #[ink(message)]
+    fn set_reward_per_point(&mut self, reward_per_point: Balance) -> Result<(), Error>;
+
+    /// Lets `reviewer` earn points for the contributors whose versions they approve. Only
+    /// the admin can designate platform reviewers; owners still add them to their documents.
+    #[ink(message)]
+    fn add_platform_reviewer(&mut self, reviewer: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn remove_platform_reviewer(&mut self, reviewer: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn is_platform_reviewer(&self, account: AccountId) -> Result<bool, Error>;
+
+    /// Pays `amount` native tokens to the holders of the document's shares, pro rata.
+    ///
+    /// Amounts too small to credit a single share are refused. The caller must have approved
+    /// this contract on the native_token contract beforehand.
+    #[ink(message)]
+    fn pay_document(&mut self, doc_id: u32, amount: Balance) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_claimable_revenue(&self, doc_id: u32, account: AccountId) -> Result<Balance, Error>;
+
+    #[ink(message)]
+    fn claim_revenue(&mut self, doc_id: u32) -> Result<Balance, Error>;
+
+    /// Called by the nft_token contract before `amount` shares move between two holders, with
+    /// the balances they had until then, so revenue paid so far stays with the sender.
+    ///
+    /// Ownership follows the shares: a recipient who ends up with a majority becomes the owner,
+    /// and an owner cannot give up the majority they hold to anyone else. Owners without a
+    /// majority move their shares freely.
+    #[ink(message)]
+    fn on_shares_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        token_id: Id,
+        amount: Balance,
+        from_balance: Balance,
+        to_balance: Balance,
+        total_supply: Balance
+    ) -> Result<(), Error>;
+
+    /// Puts a price on reading a document, or removes it with `None` to make access free.
+    /// A time-limited license must last longer than zero.
+    #[ink(message)]
+    fn set_access_terms(&mut self, doc_id: u32, terms: Option<AccessTerms>) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn get_access_terms(&self, doc_id: u32) -> Result<Option<AccessTerms>, Error>;
+
+    /// Buys a license for the caller at the current terms of the document.
+    ///
+    /// A time-limited license bought while another one is still running extends it. The owner
+    /// receives the price minus the platform fee, and the caller must have approved this contract
+    /// on the native_token contract beforehand.
+    #[ink(message)]
+    fn buy_access(&mut self, doc_id: u32) -> Result<License, Error>;
+
     #[ink(message)]
-    fn get_nfts(&self) -> Vec<NftDocument>;
+    fn get_license(&self, doc_id: u32, account: AccountId) -> Result<Option<License>, Error>;
 
+    /// Whether `account` may read the document: free documents are open to everyone, priced ones
+    /// to the owner, collaborators, reviewers and holders of a license that has not expired.
     #[ink(message)]
-    fn get_nfts_length(&self) -> u32;
+    fn has_access(&self, doc_id: u32, account: AccountId) -> Result<bool, Error>;
 
+    /// Sets the share of license sales kept by the platform, in basis points.
     #[ink(message)]
-    fn get_version_doc_length(&self, doc_id: u32) -> u8;
+    fn set_platform_fee(&mut self, platform_fee: u32) -> Result<(), Error>;
 
     #[ink(message)]
-    fn add_nft(&mut self, nft: NftDocument);
+    fn get_platform_fee(&self) -> Result<u32, Error>;
 
+    /// Returns the platform fees collected from license sales and not withdrawn yet. They are
+    /// kept apart from the reward pool.
     #[ink(message)]
-    fn get_document(&self, doc_id: u32) -> Option<NftDocument>;
+    fn get_collected_fees(&self) -> Result<Balance, Error>;
 
+    /// Sends every collected platform fee to `to`.
     #[ink(message)]
-    fn create_document(&mut self, title: String, ipfs_hash: String) -> NftDocument;
+    fn withdraw_collected_fees(&mut self, to: AccountId) -> Result<Balance, Error>;
 
+    /// Rewrites documents stored with `u8` version ids into the current layout, along with
+    /// their indexes and version attributes.
+    ///
+    /// Meant to be called by the admin right after an upgrade; documents that are already in
+    /// the current layout are skipped.
     #[ink(message)]
-    fn create_version_document(&mut self, doc_id: u32, ipfs_hash: String) -> Version;
+    fn migrate_documents(&mut self, doc_ids: Vec<u32>) -> Result<(), Error>;
 
     #[ink(message)]
-    fn get_total_supply(&self, token_id: Option<Id>) -> Balance;
+    fn get_total_supply(&self, token_id: Option<Id>) -> Result<Balance, Error>;
 
+    /// Returns the attribute the nft_token contract holds for a version: its SCALE-encoded
+    /// locators, or their blake2-256 digest for private documents.
     #[ink(message)]
-    fn get_attribute_version(&self, token_id: Id, version_id: Vec<u8>) -> Option<Vec<u8>>;
+    fn get_attribute_version(
+        &self,
+        token_id: Id,
+        version_id: u32
+    ) -> Result<Option<Vec<u8>>, Error>;
 }
\ No newline at end of file
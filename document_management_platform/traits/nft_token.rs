@@ -1,6 +1,11 @@
+use ink::prelude::vec::Vec;
 use openbrush::contracts::traits::{
     ownable::*,
-    psp37::{ extensions::{ burnable::*, mintable::*, metadata::*, enumerable::*, batch::* } },
+    psp37::{
+        Id,
+        PSP37Error,
+        extensions::{ burnable::*, mintable::*, metadata::*, enumerable::*, batch::* },
+    },
 };
 
 #[openbrush::wrapper]
@@ -10,6 +15,7 @@ pub type NftTokenRef = dyn PSP37 +
     PSP37Enumerable +
     PSP37Metadata +
     PSP37Batch +
+    NftTokenAttributes +
     Ownable;
 
 #[openbrush::trait_definition]
@@ -19,4 +25,38 @@ pub trait NftToken: PSP37 +
     PSP37Enumerable +
     PSP37Metadata +
     PSP37Batch +
-    Ownable {}
\ No newline at end of file
+    NftTokenAttributes +
+    Ownable {}
+
+#[openbrush::wrapper]
+pub type NftTokenAttributesRef = dyn NftTokenAttributes;
+
+/// Lets the owner of the token contract write `PSP37Metadata` attributes.
+#[openbrush::trait_definition]
+pub trait NftTokenAttributes {
+    #[ink(message)]
+    fn set_attribute(&mut self, id: Id, key: Vec<u8>, data: Vec<u8>) -> Result<(), PSP37Error>;
+}
+
+/// Attribute key under which the encoded content locators of a document version are stored.
+///
+/// Keys are the four-byte SCALE encoding of the `u32` version id.
+pub fn version_attribute_key(version_id: u32) -> Vec<u8> {
+    scale::Encode::encode(&version_id)
+}
+
+/// PSP37 id of the token minted to the contributor of a document version.
+///
+/// The document id fills the high half of the `u64` and the version id the low half, so
+/// version tokens never collide with the `Id::U32` share ids of documents.
+pub fn version_token_id(doc_id: u32, version_id: u32) -> Id {
+    Id::U64(((doc_id as u64) << 32) | (version_id as u64))
+}
+
+/// Splits a version token id back into its `(doc_id, version_id)` pair.
+pub fn decode_version_token_id(id: &Id) -> Option<(u32, u32)> {
+    match id {
+        Id::U64(value) => Some(((value >> 32) as u32, *value as u32)),
+        _ => None,
+    }
+}